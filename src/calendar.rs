@@ -1,5 +1,6 @@
-use crate::parser::{DueTimeRule, TodoItem};
-use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use crate::catalogue::TaskCatalogue;
+use crate::parser::{DueTimeRule, TodoItem, TodoStatus};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -15,6 +16,8 @@ pub struct IcsEvent {
     pub dtstart: DateTime<Utc>,
     pub dtend: DateTime<Utc>,
     pub alarm_minutes: Option<u16>,
+    /// Written as `STATUS:CANCELLED`.
+    pub cancelled: bool,
 }
 
 impl IcsCalendar {
@@ -54,6 +57,9 @@ impl IcsCalendar {
                 event.dtend.format("%Y%m%dT%H%M%SZ")
             ));
             output.push_str(&format!("SUMMARY:{}\r\n", escape_ics(&event.summary)));
+            if event.cancelled {
+                output.push_str("STATUS:CANCELLED\r\n");
+            }
             if let Some(mins) = event.alarm_minutes {
                 output.push_str("BEGIN:VALARM\r\n");
                 output.push_str(&format!("TRIGGER:-PT{}M\r\n", mins));
@@ -115,23 +121,112 @@ fn default_start_time() -> NaiveTime {
     NaiveTime::from_hms_opt(9, 0, 0).unwrap()
 }
 
+/// Defaults applied to every generated event.
+#[derive(Debug, Clone)]
+pub struct IcsOptions {
+    /// Start time of items without a matching due-time rule.
+    pub default_start: NaiveTime,
+    /// Length of every event.
+    pub default_duration: Duration,
+    /// Due-time rules replacing the usual ones on the days of a profile.
+    pub profile_rules: Vec<ProfileRules>,
+    /// Task catalogue whose times, durations and alarms win over the due-time rules,
+    /// except on the days of a profile.
+    pub catalogue: Option<TaskCatalogue>,
+}
+
+/// Due-time rules of the days from `from` to `to`, both included. Empty rules mean
+/// the days get no reminders.
+#[derive(Debug, Clone)]
+pub struct ProfileRules {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub rules: Vec<DueTimeRule>,
+}
+
+impl IcsOptions {
+    /// Rules of the first profile covering `date`, if any.
+    fn rules_on(&self, date: NaiveDate) -> Option<&[DueTimeRule]> {
+        self.profile_rules
+            .iter()
+            .find(|profile| (profile.from..=profile.to).contains(&date))
+            .map(|profile| profile.rules.as_slice())
+    }
+}
+
+impl Default for IcsOptions {
+    fn default() -> Self {
+        Self {
+            default_start: default_start_time(),
+            default_duration: Duration::hours(1),
+            profile_rules: Vec::new(),
+            catalogue: None,
+        }
+    }
+}
+
 pub fn generate_ics(name: &str, items: &[TodoItem], rules: &[DueTimeRule]) -> String {
+    generate_ics_with(name, items, rules, &IcsOptions::default())
+}
+
+pub fn generate_ics_with(
+    name: &str,
+    items: &[TodoItem],
+    rules: &[DueTimeRule],
+    options: &IcsOptions,
+) -> String {
     let dtstamp = Utc::now();
     let mut calendar = IcsCalendar::new(name);
 
     let local_offset = *Local::now().offset();
 
+    // Done and deferred items need no time in the calendar any more.
+    let items = items
+        .iter()
+        .filter(|item| matches!(item.status, TodoStatus::Open | TodoStatus::Cancelled));
     for item in items {
+        let cancelled = item.status == TodoStatus::Cancelled;
         let uid = generate_uid(item.date, &item.description, item.priority);
 
-        let (start_local, alarm_minutes) =
-            if let Some(rule) = DueTimeRule::lookup(rules, &item.weekday_name, item.priority) {
-                (item.date.and_time(rule.hour), Some(rule.alarm_minutes))
-            } else {
-                (item.date.and_time(default_start_time()), None)
-            };
-
-        let end_local = start_local + Duration::hours(1);
+        let profile_rules = options.rules_on(item.date);
+        let task = match (profile_rules, &options.catalogue) {
+            (None, Some(catalogue)) => catalogue.find(item),
+            _ => None,
+        };
+        // Items of the catalogue take their times from it instead of the rules.
+        let rules = profile_rules.unwrap_or(rules);
+        // The weekday comes from the date, whatever the heading says.
+        let rule = task
+            .is_none()
+            .then(|| DueTimeRule::lookup(rules, item.date.weekday(), item.priority))
+            .flatten();
+        // A time written in the item wins over both.
+        let start = item
+            .start
+            .or(task.and_then(|task| task.time))
+            .or(rule.map(|rule| rule.hour))
+            .unwrap_or(options.default_start);
+        let alarm_minutes = task
+            .and_then(|task| task.alarm_minutes)
+            .or(rule.map(|rule| rule.alarm_minutes))
+            .filter(|_| !cancelled);
+
+        let start_local = item.date.and_time(start);
+        // A range ending when it starts, e.g. `09:00-09:00`, sets no end.
+        let end = item
+            .end
+            .map(|end| item.date.and_time(end))
+            .filter(|end| *end != start_local);
+        let end_local = match end {
+            Some(end) if end > start_local => end,
+            // A range past midnight ends the next day.
+            Some(end) => end + Duration::days(1),
+            None => item
+                .duration
+                .or(task.and_then(|task| task.duration))
+                .and_then(|duration| start_local.checked_add_signed(duration))
+                .unwrap_or(start_local + options.default_duration),
+        };
 
         let dtstart = local_offset
             .from_local_datetime(&start_local)
@@ -151,6 +246,7 @@ pub fn generate_ics(name: &str, items: &[TodoItem], rules: &[DueTimeRule]) -> St
             dtstart,
             dtend,
             alarm_minutes,
+            cancelled,
         };
         calendar.add_event(event);
     }
@@ -164,7 +260,7 @@ mod tests {
     use crate::parser::{CsvParser, MdParser};
 
     fn sample_md() -> &'static str {
-        "# TODOS 202608\n\n---\n\n## 20260801 - Lunes\n\n- [ ] 1. Ejercicio\n- [ ] 2. Trabajar en RSVR, 2 horas\n- [ ] 3. Trabajar en Ematrix, 2 horas\n"
+        "# TODOS 202608\n\n---\n\n## 20260803 - Lunes\n\n- [ ] 1. Ejercicio\n- [ ] 2. Trabajar en RSVR, 2 horas\n- [ ] 3. Trabajar en Ematrix, 2 horas\n"
     }
 
     fn sample_csv() -> &'static str {
@@ -226,11 +322,11 @@ mod tests {
         let lines: Vec<&str> = ics.lines().collect();
         let dtstart_idx = lines
             .iter()
-            .position(|l| l.starts_with("DTSTART:20260801T"));
-        let dtend_idx = lines.iter().position(|l| l.starts_with("DTEND:20260801T"));
+            .position(|l| l.starts_with("DTSTART:20260803T"));
+        let dtend_idx = lines.iter().position(|l| l.starts_with("DTEND:20260803T"));
 
-        assert!(dtstart_idx.is_some(), "DTSTART for 20260801 not found");
-        assert!(dtend_idx.is_some(), "DTEND for 20260801 not found");
+        assert!(dtstart_idx.is_some(), "DTSTART for 20260803 not found");
+        assert!(dtend_idx.is_some(), "DTEND for 20260803 not found");
 
         let dtstart_line = lines[dtstart_idx.unwrap()];
         let dtend_line = lines[dtend_idx.unwrap()];
@@ -258,6 +354,38 @@ mod tests {
         assert!(ics.ends_with("END:VCALENDAR\r\n"));
     }
 
+    #[test]
+    fn test_ics_options_change_default_start_and_duration() {
+        let items = MdParser::parse(sample_md());
+        let options = IcsOptions {
+            default_start: NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+            default_duration: Duration::minutes(45),
+            ..IcsOptions::default()
+        };
+        let ics = generate_ics_with("TODOS - 202608", &items, &[], &options);
+
+        let offset = *Local::now().offset();
+        let start = item_time(&items[0], 7, 30, offset);
+        let end = item_time(&items[0], 8, 15, offset);
+        assert!(ics.contains(&format!("DTSTART:{}\r\n", start)));
+        assert!(ics.contains(&format!("DTEND:{}\r\n", end)));
+    }
+
+    fn item_time(
+        item: &crate::parser::TodoItem,
+        hour: u32,
+        min: u32,
+        offset: chrono::FixedOffset,
+    ) -> String {
+        offset
+            .from_local_datetime(&item.date.and_hms_opt(hour, min, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .to_utc()
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string()
+    }
+
     #[test]
     fn test_ics_valarm_present_for_rules() {
         let md = sample_md();
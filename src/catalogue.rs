@@ -0,0 +1,239 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use crate::config::parse_time;
+use crate::locale::parse_weekday;
+use crate::parser::TodoItem;
+use crate::recurrence::Recurrence;
+
+/// File name of the task catalogue looked up inside a templates directory.
+pub const TASKS_FILE: &str = "tasks.toml";
+
+/// Contents of a `tasks.toml` file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogueFile {
+    tasks: Vec<TaskEntry>,
+}
+
+/// A `[[tasks]]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskEntry {
+    description: String,
+    /// Weekday names or numbers, every day when missing.
+    weekdays: Option<Vec<String>>,
+    priority: u8,
+    /// Event start time, `HH:MM`.
+    time: Option<String>,
+    /// Event duration in minutes.
+    duration: Option<u32>,
+    /// Reminder minutes before the start.
+    alarm: Option<u16>,
+    /// Recurrence expression, e.g. `last friday`.
+    recurrence: Option<String>,
+}
+
+/// A task of the catalogue, planned on the days it applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogueTask {
+    pub description: String,
+    /// Weekdays the task applies to, every day when `None`.
+    pub weekdays: Option<Vec<Weekday>>,
+    pub priority: u8,
+    pub time: Option<NaiveTime>,
+    pub duration: Option<Duration>,
+    pub alarm_minutes: Option<u16>,
+    pub recurrence: Option<Recurrence>,
+}
+
+impl CatalogueTask {
+    /// Whether the task is planned on `date`: its weekdays include the date and its
+    /// recurrence, if any, matches it.
+    pub fn applies(&self, date: NaiveDate) -> bool {
+        self.weekdays
+            .as_ref()
+            .is_none_or(|weekdays| weekdays.contains(&date.weekday()))
+            && self
+                .recurrence
+                .is_none_or(|recurrence| recurrence.matches(date))
+    }
+
+    /// Description as read back by the parser, without wiki link brackets.
+    fn plain_description(&self) -> String {
+        self.description.replace("[[", "").replace("]]", "")
+    }
+}
+
+impl TaskEntry {
+    fn to_task(&self) -> Result<CatalogueTask, String> {
+        let context = |e: String| format!("task '{}': {}", self.description, e);
+        if !(1..=6).contains(&self.priority) {
+            return Err(context(format!(
+                "invalid priority {}, expected 1 to 6",
+                self.priority
+            )));
+        }
+        let weekdays = self
+            .weekdays
+            .as_ref()
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| {
+                        parse_weekday(name).ok_or_else(|| format!("unknown weekday '{}'", name))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+            .map_err(context)?;
+        Ok(CatalogueTask {
+            description: self.description.clone(),
+            weekdays,
+            priority: self.priority,
+            time: self
+                .time
+                .as_deref()
+                .map(parse_time)
+                .transpose()
+                .map_err(context)?,
+            duration: self
+                .duration
+                .map(|minutes| Duration::minutes(minutes.into())),
+            alarm_minutes: self.alarm,
+            recurrence: self
+                .recurrence
+                .as_deref()
+                .map(Recurrence::parse)
+                .transpose()
+                .map_err(context)?,
+        })
+    }
+}
+
+/// Tasks defined once with their weekdays, replacing the weekday templates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskCatalogue(pub Vec<CatalogueTask>);
+
+impl TaskCatalogue {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let file: CatalogueFile = toml::from_str(content).map_err(|e| e.to_string())?;
+        file.tasks
+            .iter()
+            .map(TaskEntry::to_task)
+            .collect::<Result<_, _>>()
+            .map(TaskCatalogue)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&content)
+    }
+
+    /// Tasks planned on `date`, by priority.
+    pub fn on(&self, date: NaiveDate) -> Vec<&CatalogueTask> {
+        let mut tasks: Vec<&CatalogueTask> =
+            self.0.iter().filter(|task| task.applies(date)).collect();
+        tasks.sort_by_key(|task| task.priority);
+        tasks
+    }
+
+    /// Task an item was generated from: same description, planned on the item's date.
+    pub fn find(&self, item: &TodoItem) -> Option<&CatalogueTask> {
+        self.0
+            .iter()
+            .find(|task| task.applies(item.date) && task.plain_description() == item.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TodoStatus;
+
+    const CATALOGUE: &str = r#"
+[[tasks]]
+description = "[[Ejercicio]]"
+priority = 1
+time = "06:00"
+duration = 45
+alarm = 10
+
+[[tasks]]
+description = "Trabajar en CBI"
+weekdays = ["lunes", "martes", "miércoles", "jueves", "viernes"]
+priority = 2
+time = "09:00"
+duration = 480
+
+[[tasks]]
+description = "Backup"
+weekdays = ["Fri"]
+priority = 6
+recurrence = "last friday"
+"#;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn descriptions(catalogue: &TaskCatalogue, date: NaiveDate) -> Vec<&str> {
+        catalogue
+            .on(date)
+            .iter()
+            .map(|task| task.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_tasks_on_a_date() {
+        let catalogue = TaskCatalogue::parse(CATALOGUE).unwrap();
+        assert_eq!(
+            descriptions(&catalogue, date(2026, 8, 1)),
+            vec!["[[Ejercicio]]"]
+        );
+        assert_eq!(
+            descriptions(&catalogue, date(2026, 8, 21)),
+            vec!["[[Ejercicio]]", "Trabajar en CBI"]
+        );
+        assert_eq!(
+            descriptions(&catalogue, date(2026, 8, 28)),
+            vec!["[[Ejercicio]]", "Trabajar en CBI", "Backup"]
+        );
+    }
+
+    #[test]
+    fn test_find_item() {
+        let catalogue = TaskCatalogue::parse(CATALOGUE).unwrap();
+        let item = |day: u32, description: &str| TodoItem {
+            date: date(2026, 8, day),
+            weekday_name: String::new(),
+            priority: 1,
+            description: description.to_string(),
+            status: TodoStatus::Open,
+            start: None,
+            end: None,
+            duration: None,
+        };
+        let exercise = catalogue.find(&item(1, "Ejercicio")).unwrap();
+        assert_eq!(exercise.duration, Some(Duration::minutes(45)));
+        assert_eq!(exercise.alarm_minutes, Some(10));
+        assert!(catalogue.find(&item(1, "Trabajar en CBI")).is_none());
+        assert!(catalogue.find(&item(3, "Trabajar en CBI")).is_some());
+    }
+
+    #[test]
+    fn test_invalid_catalogue() {
+        for content in [
+            "[[tasks]]\ndescription = \"A\"\npriority = 7\n",
+            "[[tasks]]\ndescription = \"A\"\npriority = 1\nweekdays = [\"someday\"]\n",
+            "[[tasks]]\ndescription = \"A\"\npriority = 1\ntime = \"9am\"\n",
+            "[[tasks]]\ndescription = \"A\"\npriority = 1\nrecurrence = \"never\"\n",
+            "[[tasks]]\ndescription = \"A\"\npriority = 1\ncolor = \"red\"\n",
+        ] {
+            assert!(TaskCatalogue::parse(content).is_err(), "{}", content);
+        }
+    }
+}
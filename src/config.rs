@@ -0,0 +1,1195 @@
+use chrono::NaiveTime;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::catalogue::TASKS_FILE;
+use crate::frontmatter::Frontmatter;
+use crate::holidays::HOLIDAYS_FILES;
+use crate::locale::Locale;
+use crate::output::{DEFAULT_DAILY_PATTERN, DailyNotes, Layout, parse_daily_pattern};
+use crate::profiles::{Profile, Profiles};
+use crate::recurrence::RECURRING_FILE;
+use crate::templates::RULES_FILE;
+
+pub const PATH_ENV: &str = "TODOS_DEFAULT_PATH";
+pub const TEMPLATES_DIR_ENV: &str = "TODOS_TEMPLATES_DIR";
+pub const RULES_ENV: &str = "TODOS_RULES";
+pub const CALENDAR_NAME_ENV: &str = "TODOS_CALENDAR_NAME";
+pub const EVENT_DURATION_ENV: &str = "TODOS_EVENT_DURATION";
+pub const START_TIME_ENV: &str = "TODOS_START_TIME";
+pub const LOCALE_ENV: &str = "TODOS_LOCALE";
+pub const LAYOUT_ENV: &str = "TODOS_LAYOUT";
+pub const DAILY_FOLDER_ENV: &str = "TODOS_DAILY_FOLDER";
+pub const DAILY_PATTERN_ENV: &str = "TODOS_DAILY_PATTERN";
+pub const FRONTMATTER_ENV: &str = "TODOS_FRONTMATTER";
+pub const TAGS_ENV: &str = "TODOS_TAGS";
+pub const HOLIDAYS_ENV: &str = "TODOS_HOLIDAYS";
+pub const PROFILES_ENV: &str = "TODOS_PROFILES";
+pub const RECURRING_ENV: &str = "TODOS_RECURRING";
+pub const TASKS_ENV: &str = "TODOS_TASKS";
+pub const STRICT_ENV: &str = "TODOS_STRICT";
+
+/// Name of the templates directory looked up in the current and config directories.
+pub const TEMPLATES_DIR: &str = "templates";
+/// Global configuration file inside the config directory.
+pub const CONFIG_FILE: &str = "config.toml";
+/// Per-directory configuration file, looked up in the current directory.
+pub const LOCAL_CONFIG_FILE: &str = ".todos-cli.toml";
+
+/// Where a resolved setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Flag(&'static str),
+    Env(&'static str),
+    /// Set in the per-directory config file.
+    LocalConfig(PathBuf),
+    /// Set in the global config file.
+    GlobalConfig(PathBuf),
+    /// Found next to the current directory.
+    LocalDir,
+    /// Found under `$XDG_CONFIG_HOME/todos-cli/`.
+    ConfigDir,
+    BuiltIn,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Flag(flag) => write!(f, "flag {}", flag),
+            Source::Env(var) => write!(f, "env {}", var),
+            Source::LocalConfig(path) | Source::GlobalConfig(path) => {
+                write!(f, "config file {}", path.display())
+            }
+            Source::LocalDir => write!(f, "current directory"),
+            Source::ConfigDir => write!(f, "config directory"),
+            Source::BuiltIn => write!(f, "built-in"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Setting<T> {
+    fn new(value: T, source: Source) -> Self {
+        Self { value, source }
+    }
+}
+
+/// Values given on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub path: Option<PathBuf>,
+    pub templates_dir: Option<PathBuf>,
+    pub rules: Option<PathBuf>,
+    pub calendar_name: Option<String>,
+    pub event_duration: Option<u32>,
+    pub start_time: Option<NaiveTime>,
+    pub locale: Option<Locale>,
+    pub layout: Option<Layout>,
+    pub daily_folder: Option<PathBuf>,
+    pub daily_pattern: Option<String>,
+    pub frontmatter: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    pub holidays: Option<PathBuf>,
+    pub profiles: Option<Vec<Profile>>,
+    pub recurring: Option<PathBuf>,
+    pub tasks: Option<PathBuf>,
+    pub strict: Option<bool>,
+}
+
+/// Contents of a `config.toml` file. Relative paths are relative to the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub path: Option<PathBuf>,
+    pub templates_dir: Option<PathBuf>,
+    pub rules: Option<PathBuf>,
+    pub calendar_name: Option<String>,
+    /// Default event duration in minutes.
+    pub event_duration: Option<u32>,
+    /// Default event start time, `HH:MM`.
+    pub start_time: Option<String>,
+    /// Language of weekday names and messages, `es` or `en`.
+    pub locale: Option<String>,
+    /// Markdown layout, `single` or `daily`.
+    pub layout: Option<String>,
+    /// Folder of the daily notes, relative to `path`.
+    pub daily_folder: Option<PathBuf>,
+    /// Daily note file name, a chrono `strftime` pattern.
+    pub daily_pattern: Option<String>,
+    /// Write YAML frontmatter to the TODOS files.
+    pub frontmatter: Option<bool>,
+    /// Tags of the YAML frontmatter.
+    pub tags: Option<Vec<String>>,
+    /// Holidays list, CSV or ICS.
+    pub holidays: Option<PathBuf>,
+    /// Date ranges with their own templates and due-time rules, `[[profiles]]` tables.
+    pub profiles: Option<Vec<ProfileEntry>>,
+    /// Recurring tasks CSV.
+    pub recurring: Option<PathBuf>,
+    /// Task catalogue replacing the weekday templates.
+    pub tasks: Option<PathBuf>,
+    /// Fail when a parsed file has problems.
+    pub strict: Option<bool>,
+}
+
+/// A `[[profiles]]` table of a config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileEntry {
+    pub name: String,
+    /// First day, `YYYY-MM-DD`.
+    pub from: String,
+    /// Last day, `YYYY-MM-DD`, included.
+    pub to: String,
+    /// Template rendering every day of the range.
+    pub template: Option<String>,
+    /// Templates directory of the range.
+    pub templates_dir: Option<PathBuf>,
+    /// Due-time rules CSV of the range; without it the days get no reminders.
+    pub rules: Option<PathBuf>,
+}
+
+impl ProfileEntry {
+    fn to_profile(&self, base_dir: &Path) -> Result<Profile, String> {
+        Ok(Profile {
+            template: self.template.clone(),
+            templates_dir: self
+                .templates_dir
+                .as_deref()
+                .map(|dir| resolve_path(dir, base_dir)),
+            rules: self
+                .rules
+                .as_deref()
+                .map(|rules| resolve_path(rules, base_dir)),
+            ..Profile::new(&self.name, &self.from, &self.to)?
+        })
+    }
+}
+
+impl ConfigFile {
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+/// Directories searched for configuration, templates and rules.
+#[derive(Debug, Clone)]
+pub struct SearchPaths {
+    pub local_dir: PathBuf,
+    pub config_dir: Option<PathBuf>,
+}
+
+impl SearchPaths {
+    pub fn from_env() -> Self {
+        Self {
+            local_dir: PathBuf::from("."),
+            config_dir: config_dir(),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/todos-cli`, falling back to `$HOME/.config/todos-cli`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_STATE_HOME/todos-cli`, falling back to `$HOME/.local/state/todos-cli`. Keeps
+/// the merge bases.
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// `todos-cli` inside the directory of the XDG variable `var`, or inside `home_dir`
+/// relative to `$HOME` when it is unset.
+fn xdg_dir(var: &str, home_dir: &str) -> Option<PathBuf> {
+    let non_empty = |var: &str| std::env::var_os(var).filter(|value| !value.is_empty());
+    non_empty(var)
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(home_dir)))
+        .map(|dir| dir.join("todos-cli"))
+}
+
+/// Parses a `HH:MM` time.
+pub fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|e| format!("invalid time '{}', expected HH:MM: {}", value, e))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!(
+            "invalid boolean '{}', expected true or false",
+            value
+        )),
+    }
+}
+
+fn parse_tags(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_profiles(value: &str) -> Result<Vec<Profile>, String> {
+    value
+        .split(',')
+        .filter(|spec| !spec.trim().is_empty())
+        .map(Profile::parse)
+        .collect()
+}
+
+fn parse_minutes(value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid duration '{}', expected minutes: {}", value, e))
+}
+
+/// Expands a leading `~/` and makes relative paths relative to `base`.
+fn resolve_path(path: &Path, base: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return Path::new(&home).join(rest);
+    }
+    if path.is_relative() {
+        base.join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// A config file that was found, with where it was found.
+struct Layer {
+    source: Source,
+    base_dir: PathBuf,
+    file: ConfigFile,
+}
+
+fn read_layer(path: PathBuf, source: Source) -> Result<Option<Layer>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let file = ConfigFile::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let base_dir = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    Ok(Some(Layer {
+        source,
+        base_dir,
+        file,
+    }))
+}
+
+/// Returns the first candidate that has a value.
+fn first<T>(candidates: Vec<(Option<T>, Source)>) -> Option<Setting<T>> {
+    candidates
+        .into_iter()
+        .find_map(|(value, source)| value.map(|value| Setting::new(value, source)))
+}
+
+/// A value as written in a config file, read into a setting of type `T`.
+trait FileValue<T> {
+    /// Reads the value, resolving paths against `base_dir`.
+    fn read(&self, base_dir: &Path) -> Result<T, String>;
+}
+
+impl FileValue<String> for String {
+    fn read(&self, _: &Path) -> Result<String, String> {
+        Ok(self.clone())
+    }
+}
+
+/// A `HH:MM` time.
+impl FileValue<NaiveTime> for String {
+    fn read(&self, _: &Path) -> Result<NaiveTime, String> {
+        parse_time(self)
+    }
+}
+
+impl FileValue<Locale> for String {
+    fn read(&self, _: &Path) -> Result<Locale, String> {
+        self.parse()
+    }
+}
+
+impl FileValue<Layout> for String {
+    fn read(&self, _: &Path) -> Result<Layout, String> {
+        self.parse()
+    }
+}
+
+/// A path relative to the config file.
+impl FileValue<PathBuf> for PathBuf {
+    fn read(&self, base_dir: &Path) -> Result<PathBuf, String> {
+        Ok(resolve_path(self, base_dir))
+    }
+}
+
+/// A path kept as written, e.g. `daily_folder`, which is relative to `path`.
+impl FileValue<PathBuf> for Path {
+    fn read(&self, _: &Path) -> Result<PathBuf, String> {
+        Ok(self.to_path_buf())
+    }
+}
+
+impl FileValue<u32> for u32 {
+    fn read(&self, _: &Path) -> Result<u32, String> {
+        Ok(*self)
+    }
+}
+
+impl FileValue<bool> for bool {
+    fn read(&self, _: &Path) -> Result<bool, String> {
+        Ok(*self)
+    }
+}
+
+impl FileValue<Vec<String>> for Vec<String> {
+    fn read(&self, _: &Path) -> Result<Vec<String>, String> {
+        Ok(self.clone())
+    }
+}
+
+impl FileValue<Vec<Profile>> for Vec<ProfileEntry> {
+    fn read(&self, base_dir: &Path) -> Result<Vec<Profile>, String> {
+        self.iter()
+            .map(|entry| entry.to_profile(base_dir))
+            .collect()
+    }
+}
+
+fn parse_path(value: &str) -> Result<PathBuf, String> {
+    Ok(PathBuf::from(value))
+}
+
+fn parse_string(value: &str) -> Result<String, String> {
+    Ok(value.to_string())
+}
+
+/// Looks settings up in the flags, the env vars and the config files, in that order.
+struct Resolver<E> {
+    env: E,
+    layers: Vec<Layer>,
+}
+
+impl<E: Fn(&str) -> Option<String>> Resolver<E> {
+    /// The first of `flag`, the env var `var` and the config files that sets a value.
+    /// Only that value is parsed, so an invalid value below it is never an error.
+    fn lookup<T, F: FileValue<T> + ?Sized>(
+        &self,
+        flag: (Option<T>, &'static str),
+        var: &'static str,
+        parse: fn(&str) -> Result<T, String>,
+        get: fn(&ConfigFile) -> Option<&F>,
+    ) -> Result<Option<Setting<T>>, String> {
+        if let (Some(value), name) = flag {
+            return Ok(Some(Setting::new(value, Source::Flag(name))));
+        }
+        if let Some(value) = (self.env)(var).filter(|value| !value.is_empty()) {
+            let source = Source::Env(var);
+            return match parse(&value) {
+                Ok(value) => Ok(Some(Setting::new(value, source))),
+                Err(e) => Err(format!("{}: {}", source, e)),
+            };
+        }
+        self.layers
+            .iter()
+            .find_map(|layer| {
+                let value = get(&layer.file)?;
+                Some(match value.read(&layer.base_dir) {
+                    Ok(value) => Ok(Setting::new(value, layer.source.clone())),
+                    Err(e) => Err(format!("{}: {}", layer.source, e)),
+                })
+            })
+            .transpose()
+    }
+
+    /// Like `lookup`, falling back to the built-in `default`.
+    fn setting<T, F: FileValue<T> + ?Sized>(
+        &self,
+        flag: (Option<T>, &'static str),
+        var: &'static str,
+        parse: fn(&str) -> Result<T, String>,
+        get: fn(&ConfigFile) -> Option<&F>,
+        default: T,
+    ) -> Result<Setting<T>, String> {
+        Ok(self
+            .lookup(flag, var, parse, get)?
+            .unwrap_or_else(|| Setting::new(default, Source::BuiltIn)))
+    }
+}
+
+/// An optional setting: `setting`, then the first of `found`, then `None`.
+fn optional<T>(setting: Option<Setting<T>>, found: Vec<(Option<T>, Source)>) -> Setting<Option<T>> {
+    setting
+        .or_else(|| first(found))
+        .map(|setting| Setting::new(Some(setting.value), setting.source))
+        .unwrap_or_else(|| Setting::new(None, Source::BuiltIn))
+}
+
+/// Every persistent setting, resolved from flags, env vars, config files and defaults.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Output directory of the generated files.
+    pub path: Setting<PathBuf>,
+    /// Directory overriding the built-in templates, `None` for built-in only.
+    pub templates_dir: Setting<Option<PathBuf>>,
+    /// Due-time rules CSV, `None` for the built-in rules.
+    pub rules: Setting<Option<PathBuf>>,
+    /// Calendar name of the ICS files, `None` to use the file name.
+    pub calendar_name: Setting<Option<String>>,
+    /// Default event duration in minutes.
+    pub event_duration: Setting<u32>,
+    /// Start time of events without a due-time rule.
+    pub start_time: Setting<NaiveTime>,
+    /// Language of weekday names and messages.
+    pub locale: Setting<Locale>,
+    /// Single TODOS file or daily notes.
+    pub layout: Setting<Layout>,
+    /// Folder of the daily notes, relative to `path`.
+    pub daily_folder: Setting<PathBuf>,
+    /// File name pattern of the daily notes.
+    pub daily_pattern: Setting<String>,
+    /// Whether TODOS files start with YAML frontmatter.
+    pub frontmatter: Setting<bool>,
+    /// Tags of the YAML frontmatter.
+    pub tags: Setting<Vec<String>>,
+    /// Holidays list, CSV or ICS, `None` for no holidays.
+    pub holidays: Setting<Option<PathBuf>>,
+    /// Date ranges with their own templates and due-time rules.
+    pub profiles: Setting<Profiles>,
+    /// Recurring tasks CSV, `None` for no recurring tasks.
+    pub recurring: Setting<Option<PathBuf>>,
+    /// Task catalogue TOML, `None` to render from the weekday templates.
+    pub tasks: Setting<Option<PathBuf>>,
+    /// Whether problems found while parsing a file make the command fail.
+    pub strict: Setting<bool>,
+}
+
+impl Settings {
+    /// Resolves every setting from flags, then env vars, then the per-directory config
+    /// file, then the global config file, then files found in the current and config
+    /// directories, then the built-in defaults.
+    pub fn resolve(
+        overrides: &Overrides,
+        env: impl Fn(&str) -> Option<String>,
+        search: &SearchPaths,
+    ) -> Result<Self, String> {
+        let mut layers = Vec::new();
+        let local_config = search.local_dir.join(LOCAL_CONFIG_FILE);
+        layers.extend(read_layer(
+            local_config.clone(),
+            Source::LocalConfig(local_config),
+        )?);
+        if let Some(config_dir) = &search.config_dir {
+            let global_config = config_dir.join(CONFIG_FILE);
+            layers.extend(read_layer(
+                global_config.clone(),
+                Source::GlobalConfig(global_config),
+            )?);
+        }
+        let r = Resolver { env, layers };
+        let o = overrides;
+
+        let path = r.setting(
+            (o.path.clone(), "--path"),
+            PATH_ENV,
+            parse_path,
+            |file| file.path.as_ref(),
+            PathBuf::from("."),
+        )?;
+
+        let templates_dir = optional(
+            r.lookup(
+                (o.templates_dir.clone(), "--templates-dir"),
+                TEMPLATES_DIR_ENV,
+                parse_path,
+                |file| file.templates_dir.as_ref(),
+            )?,
+            vec![
+                (
+                    Some(search.local_dir.join(TEMPLATES_DIR)).filter(|dir| dir.is_dir()),
+                    Source::LocalDir,
+                ),
+                (
+                    search
+                        .config_dir
+                        .as_ref()
+                        .map(|config| config.join(TEMPLATES_DIR))
+                        .filter(|dir| dir.is_dir()),
+                    Source::ConfigDir,
+                ),
+            ],
+        );
+
+        // Files found in the templates directory, then in the config directory.
+        let file_in = |dir: Option<&PathBuf>, name: &str| {
+            dir.map(|dir| dir.join(name)).filter(|path| path.is_file())
+        };
+        let found = |name: &str| {
+            vec![
+                (
+                    file_in(templates_dir.value.as_ref(), name),
+                    templates_dir.source.clone(),
+                ),
+                (file_in(search.config_dir.as_ref(), name), Source::ConfigDir),
+            ]
+        };
+
+        let rules = optional(
+            r.lookup(
+                (o.rules.clone(), "--rules"),
+                RULES_ENV,
+                parse_path,
+                |file| file.rules.as_ref(),
+            )?,
+            found(RULES_FILE),
+        );
+
+        let in_dir = |dir: Option<&PathBuf>| {
+            dir.and_then(|dir| {
+                HOLIDAYS_FILES
+                    .iter()
+                    .map(|name| dir.join(name))
+                    .find(|path| path.is_file())
+            })
+        };
+        let holidays = optional(
+            r.lookup(
+                (o.holidays.clone(), "--holidays"),
+                HOLIDAYS_ENV,
+                parse_path,
+                |file| file.holidays.as_ref(),
+            )?,
+            vec![
+                (
+                    in_dir(templates_dir.value.as_ref()),
+                    templates_dir.source.clone(),
+                ),
+                (in_dir(search.config_dir.as_ref()), Source::ConfigDir),
+            ],
+        );
+
+        let recurring = optional(
+            r.lookup(
+                (o.recurring.clone(), "--recurring"),
+                RECURRING_ENV,
+                parse_path,
+                |file| file.recurring.as_ref(),
+            )?,
+            found(RECURRING_FILE),
+        );
+
+        let tasks = optional(
+            r.lookup(
+                (o.tasks.clone(), "--tasks"),
+                TASKS_ENV,
+                parse_path,
+                |file| file.tasks.as_ref(),
+            )?,
+            found(TASKS_FILE),
+        );
+
+        let calendar_name = optional(
+            r.lookup(
+                (o.calendar_name.clone(), "--calendar-name"),
+                CALENDAR_NAME_ENV,
+                parse_string,
+                |file| file.calendar_name.as_ref(),
+            )?,
+            Vec::new(),
+        );
+
+        let event_duration = r.setting(
+            (o.event_duration, "--event-duration"),
+            EVENT_DURATION_ENV,
+            parse_minutes,
+            |file| file.event_duration.as_ref(),
+            60,
+        )?;
+
+        let start_time = r.setting(
+            (o.start_time, "--start-time"),
+            START_TIME_ENV,
+            parse_time,
+            |file| file.start_time.as_ref(),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+        )?;
+
+        let locale = r.setting(
+            (o.locale, "--locale"),
+            LOCALE_ENV,
+            str::parse,
+            |file| file.locale.as_ref(),
+            Locale::default(),
+        )?;
+
+        let layout = r.setting(
+            (o.layout, "--layout"),
+            LAYOUT_ENV,
+            str::parse,
+            |file| file.layout.as_ref(),
+            Layout::default(),
+        )?;
+
+        let daily_folder = r.setting(
+            (o.daily_folder.clone(), "--daily-folder"),
+            DAILY_FOLDER_ENV,
+            parse_path,
+            |file| file.daily_folder.as_deref(),
+            DailyNotes::default().folder,
+        )?;
+
+        let daily_pattern = r.setting(
+            (o.daily_pattern.clone(), "--daily-pattern"),
+            DAILY_PATTERN_ENV,
+            parse_string,
+            |file| file.daily_pattern.as_ref(),
+            DEFAULT_DAILY_PATTERN.to_string(),
+        )?;
+        parse_daily_pattern(&daily_pattern.value)
+            .map_err(|e| format!("{}: {}", daily_pattern.source, e))?;
+
+        let frontmatter = r.setting(
+            (o.frontmatter, "--frontmatter"),
+            FRONTMATTER_ENV,
+            parse_bool,
+            |file| file.frontmatter.as_ref(),
+            false,
+        )?;
+
+        let tags = r.setting(
+            (o.tags.clone(), "--tags"),
+            TAGS_ENV,
+            |value| Ok(parse_tags(value)),
+            |file| file.tags.as_ref(),
+            Frontmatter::default().tags,
+        )?;
+
+        let profiles = r.setting(
+            (o.profiles.clone(), "--profile"),
+            PROFILES_ENV,
+            parse_profiles,
+            |file| file.profiles.as_ref(),
+            Vec::new(),
+        )?;
+        let profiles = Setting::new(Profiles(profiles.value), profiles.source);
+
+        let strict = r.setting(
+            (o.strict, "--strict"),
+            STRICT_ENV,
+            parse_bool,
+            |file| file.strict.as_ref(),
+            false,
+        )?;
+
+        Ok(Self {
+            path,
+            templates_dir,
+            rules,
+            calendar_name,
+            event_duration,
+            start_time,
+            locale,
+            layout,
+            daily_folder,
+            daily_pattern,
+            frontmatter,
+            tags,
+            holidays,
+            profiles,
+            recurring,
+            tasks,
+            strict,
+        })
+    }
+
+    /// Frontmatter to write, `None` when disabled.
+    pub fn frontmatter(&self) -> Option<Frontmatter> {
+        self.frontmatter.value.then(|| Frontmatter {
+            tags: self.tags.value.clone(),
+        })
+    }
+
+    /// Where daily notes go, `None` for the single file layout.
+    pub fn daily_notes(&self) -> Option<DailyNotes> {
+        (self.layout.value == Layout::Daily).then(|| DailyNotes {
+            folder: self.daily_folder.value.clone(),
+            pattern: self.daily_pattern.value.clone(),
+        })
+    }
+
+    /// Resolves the settings against the process environment.
+    pub fn from_env(overrides: &Overrides) -> Result<Self, String> {
+        Self::resolve(
+            overrides,
+            |var| std::env::var(var).ok(),
+            &SearchPaths::from_env(),
+        )
+    }
+
+    /// Every setting as `(key, value, source)`, for display.
+    pub fn entries(&self) -> Vec<(&'static str, String, &Source)> {
+        let optional_path = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map(|path| format!("{:?}", path.display().to_string()))
+                .unwrap_or_else(|| String::from("(built-in)"))
+        };
+        vec![
+            (
+                "path",
+                format!("{:?}", self.path.value.display().to_string()),
+                &self.path.source,
+            ),
+            (
+                "templates_dir",
+                optional_path(&self.templates_dir.value),
+                &self.templates_dir.source,
+            ),
+            (
+                "rules",
+                optional_path(&self.rules.value),
+                &self.rules.source,
+            ),
+            (
+                "calendar_name",
+                self.calendar_name
+                    .value
+                    .as_ref()
+                    .map(|name| format!("{:?}", name))
+                    .unwrap_or_else(|| String::from("(file name)")),
+                &self.calendar_name.source,
+            ),
+            (
+                "event_duration",
+                self.event_duration.value.to_string(),
+                &self.event_duration.source,
+            ),
+            (
+                "start_time",
+                format!("\"{}\"", self.start_time.value.format("%H:%M")),
+                &self.start_time.source,
+            ),
+            (
+                "locale",
+                format!("\"{}\"", self.locale.value),
+                &self.locale.source,
+            ),
+            (
+                "layout",
+                format!("\"{}\"", self.layout.value),
+                &self.layout.source,
+            ),
+            (
+                "daily_folder",
+                format!("{:?}", self.daily_folder.value.display().to_string()),
+                &self.daily_folder.source,
+            ),
+            (
+                "daily_pattern",
+                format!("{:?}", self.daily_pattern.value),
+                &self.daily_pattern.source,
+            ),
+            (
+                "frontmatter",
+                self.frontmatter.value.to_string(),
+                &self.frontmatter.source,
+            ),
+            ("tags", format!("{:?}", self.tags.value), &self.tags.source),
+            (
+                "holidays",
+                self.holidays
+                    .value
+                    .as_ref()
+                    .map(|path| format!("{:?}", path.display().to_string()))
+                    .unwrap_or_else(|| String::from("(none)")),
+                &self.holidays.source,
+            ),
+            (
+                "profiles",
+                if self.profiles.value.0.is_empty() {
+                    String::from("(none)")
+                } else {
+                    let profiles: Vec<String> = self
+                        .profiles
+                        .value
+                        .0
+                        .iter()
+                        .map(Profile::to_string)
+                        .collect();
+                    format!("{:?}", profiles)
+                },
+                &self.profiles.source,
+            ),
+            (
+                "recurring",
+                self.recurring
+                    .value
+                    .as_ref()
+                    .map(|path| format!("{:?}", path.display().to_string()))
+                    .unwrap_or_else(|| String::from("(none)")),
+                &self.recurring.source,
+            ),
+            (
+                "tasks",
+                self.tasks
+                    .value
+                    .as_ref()
+                    .map(|path| format!("{:?}", path.display().to_string()))
+                    .unwrap_or_else(|| String::from("(templates)")),
+                &self.tasks.source,
+            ),
+            ("strict", self.strict.value.to_string(), &self.strict.source),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("todos-config-{}-{}", prefix, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_builtin_when_nothing_is_found() {
+        let dir = temp_dir("builtin");
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: Some(dir.join("config")),
+        };
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        assert_eq!(settings.path.value, PathBuf::from("."));
+        assert_eq!(settings.templates_dir.value, None);
+        assert_eq!(settings.templates_dir.source, Source::BuiltIn);
+        assert_eq!(settings.rules.value, None);
+        assert_eq!(settings.calendar_name.value, None);
+        assert_eq!(settings.event_duration.value, 60);
+        assert_eq!(
+            settings.start_time.value.format("%H:%M").to_string(),
+            "09:00"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lookup_order() {
+        let dir = temp_dir("order");
+        let config = dir.join("config");
+        fs::create_dir_all(config.join(TEMPLATES_DIR)).unwrap();
+        fs::write(config.join(RULES_FILE), "weekday,priority,hour,minutes\n").unwrap();
+        let search = SearchPaths {
+            local_dir: dir.join("cwd"),
+            config_dir: Some(config.clone()),
+        };
+
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        assert_eq!(
+            settings.templates_dir.value,
+            Some(config.join(TEMPLATES_DIR))
+        );
+        assert_eq!(settings.templates_dir.source, Source::ConfigDir);
+        assert_eq!(settings.rules.value, Some(config.join(RULES_FILE)));
+
+        fs::create_dir_all(dir.join("cwd").join(TEMPLATES_DIR)).unwrap();
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        assert_eq!(settings.templates_dir.source, Source::LocalDir);
+        assert_eq!(settings.rules.source, Source::ConfigDir);
+
+        let env = |var: &str| (var == TEMPLATES_DIR_ENV).then(|| "/env/templates".to_string());
+        let settings = Settings::resolve(&Overrides::default(), env, &search).unwrap();
+        assert_eq!(
+            settings.templates_dir.value,
+            Some(PathBuf::from("/env/templates"))
+        );
+        assert_eq!(
+            settings.templates_dir.source,
+            Source::Env(TEMPLATES_DIR_ENV)
+        );
+
+        let overrides = Overrides {
+            templates_dir: Some(PathBuf::from("/flag/templates")),
+            rules: Some(PathBuf::from("/flag/rules.csv")),
+            ..Overrides::default()
+        };
+        let settings = Settings::resolve(&overrides, env, &search).unwrap();
+        assert_eq!(
+            settings.templates_dir.value,
+            Some(PathBuf::from("/flag/templates"))
+        );
+        assert_eq!(settings.rules.source, Source::Flag("--rules"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rules_follow_the_templates_dir() {
+        let dir = temp_dir("rules");
+        fs::write(dir.join(RULES_FILE), "weekday,priority,hour,minutes\n").unwrap();
+        fs::write(dir.join("holidays.ics"), "BEGIN:VCALENDAR\n").unwrap();
+        fs::write(dir.join(RECURRING_FILE), "rule,priority,description\n").unwrap();
+        fs::write(dir.join(TASKS_FILE), "tasks = []\n").unwrap();
+        let search = SearchPaths {
+            local_dir: dir.join("cwd"),
+            config_dir: None,
+        };
+        let overrides = Overrides {
+            templates_dir: Some(dir.clone()),
+            ..Overrides::default()
+        };
+        let settings = Settings::resolve(&overrides, no_env, &search).unwrap();
+        assert_eq!(settings.rules.value, Some(dir.join(RULES_FILE)));
+        assert_eq!(settings.rules.source, Source::Flag("--templates-dir"));
+        assert_eq!(settings.holidays.value, Some(dir.join("holidays.ics")));
+        assert_eq!(settings.recurring.value, Some(dir.join(RECURRING_FILE)));
+        assert_eq!(settings.tasks.value, Some(dir.join(TASKS_FILE)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_files_are_merged() {
+        let dir = temp_dir("files");
+        let config = dir.join("config");
+        let cwd = dir.join("cwd");
+        fs::create_dir_all(&config).unwrap();
+        fs::create_dir_all(&cwd).unwrap();
+        fs::write(
+            config.join(CONFIG_FILE),
+            "path = \"/vault/TODOS\"\ntemplates_dir = \"shared\"\ncalendar_name = \"TODOS\"\nevent_duration = 45\nstart_time = \"08:30\"\n",
+        )
+        .unwrap();
+        fs::write(
+            cwd.join(LOCAL_CONFIG_FILE),
+            "event_duration = 30\nlocale = \"en\"\n",
+        )
+        .unwrap();
+        let search = SearchPaths {
+            local_dir: cwd.clone(),
+            config_dir: Some(config.clone()),
+        };
+
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        let global = Source::GlobalConfig(config.join(CONFIG_FILE));
+        assert_eq!(settings.path.value, PathBuf::from("/vault/TODOS"));
+        assert_eq!(settings.path.source, global);
+        assert_eq!(settings.templates_dir.value, Some(config.join("shared")));
+        assert_eq!(settings.calendar_name.value.as_deref(), Some("TODOS"));
+        assert_eq!(settings.event_duration.value, 30);
+        assert_eq!(
+            settings.event_duration.source,
+            Source::LocalConfig(cwd.join(LOCAL_CONFIG_FILE))
+        );
+        assert_eq!(
+            settings.start_time.value.format("%H:%M").to_string(),
+            "08:30"
+        );
+        assert_eq!(settings.locale.value, Locale::En);
+        assert_eq!(settings.daily_notes(), None);
+
+        let env = |var: &str| (var == EVENT_DURATION_ENV).then(|| "90".to_string());
+        let overrides = Overrides {
+            path: Some(PathBuf::from("out")),
+            ..Overrides::default()
+        };
+        let settings = Settings::resolve(&overrides, env, &search).unwrap();
+        assert_eq!(settings.path.value, PathBuf::from("out"));
+        assert_eq!(settings.event_duration.value, 90);
+        assert_eq!(
+            settings.event_duration.source,
+            Source::Env(EVENT_DURATION_ENV)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_daily_notes_settings() {
+        let dir = temp_dir("daily");
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE),
+            "layout = \"daily\"\ndaily_folder = \"Diario\"\n",
+        )
+        .unwrap();
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: None,
+        };
+        let env = |var: &str| (var == DAILY_PATTERN_ENV).then(|| "%Y%m%d".to_string());
+        let settings = Settings::resolve(&Overrides::default(), env, &search).unwrap();
+        assert_eq!(
+            settings.daily_notes(),
+            Some(DailyNotes {
+                folder: PathBuf::from("Diario"),
+                pattern: "%Y%m%d".to_string(),
+            })
+        );
+
+        let overrides = Overrides {
+            layout: Some(Layout::Single),
+            ..Overrides::default()
+        };
+        let settings = Settings::resolve(&overrides, env, &search).unwrap();
+        assert_eq!(settings.daily_notes(), None);
+
+        let env = |var: &str| (var == DAILY_PATTERN_ENV).then(|| "%Q".to_string());
+        assert!(Settings::resolve(&Overrides::default(), env, &search).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_frontmatter_settings() {
+        let dir = temp_dir("frontmatter");
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE),
+            "frontmatter = true\ntags = [\"todos\", \"ivy-lee\"]\n",
+        )
+        .unwrap();
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: None,
+        };
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        assert_eq!(
+            settings.frontmatter().map(|frontmatter| frontmatter.tags),
+            Some(vec!["todos".to_string(), "ivy-lee".to_string()])
+        );
+
+        let env = |var: &str| match var {
+            FRONTMATTER_ENV => Some("no".to_string()),
+            TAGS_ENV => Some("plan, mes".to_string()),
+            _ => None,
+        };
+        let settings = Settings::resolve(&Overrides::default(), env, &search).unwrap();
+        assert_eq!(settings.frontmatter(), None);
+        assert_eq!(settings.tags.value, vec!["plan", "mes"]);
+
+        let env = |var: &str| (var == FRONTMATTER_ENV).then(|| "maybe".to_string());
+        assert!(Settings::resolve(&Overrides::default(), env, &search).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_strict_setting() {
+        let dir = temp_dir("strict");
+        fs::write(dir.join(LOCAL_CONFIG_FILE), "strict = true\n").unwrap();
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: None,
+        };
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        assert!(settings.strict.value);
+
+        let env = |var: &str| (var == STRICT_ENV).then(|| "off".to_string());
+        let settings = Settings::resolve(&Overrides::default(), env, &search).unwrap();
+        assert!(!settings.strict.value);
+        assert_eq!(settings.strict.source, Source::Env(STRICT_ENV));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_profile_settings() {
+        let dir = temp_dir("profiles");
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE),
+            "[[profiles]]\nname = \"viaje\"\nfrom = \"2026-08-03\"\nto = \"2026-08-09\"\n\
+             template = \"travel.md\"\nrules = \"travel.csv\"\n",
+        )
+        .unwrap();
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: None,
+        };
+        let settings = Settings::resolve(&Overrides::default(), no_env, &search).unwrap();
+        let profile = &settings.profiles.value.0[0];
+        assert_eq!(profile.to_string(), "viaje 2026-08-03..2026-08-09");
+        assert_eq!(profile.template.as_deref(), Some("travel.md"));
+        assert_eq!(profile.rules, Some(dir.join("travel.csv")));
+
+        let env =
+            |var: &str| (var == PROFILES_ENV).then(|| "beach.md:2026-12-20:2026-12-31".to_string());
+        let settings = Settings::resolve(&Overrides::default(), env, &search).unwrap();
+        assert_eq!(settings.profiles.value.0[0].name, "beach");
+        assert_eq!(settings.profiles.source, Source::Env(PROFILES_ENV));
+
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE),
+            "[[profiles]]\nname = \"viaje\"\nfrom = \"2026-08-09\"\nto = \"2026-08-03\"\n",
+        )
+        .unwrap();
+        assert!(Settings::resolve(&Overrides::default(), no_env, &search).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_only_the_selected_value_is_parsed() {
+        let dir = temp_dir("selected");
+        fs::write(dir.join(LOCAL_CONFIG_FILE), "start_time = \"9am\"\n").unwrap();
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: None,
+        };
+        let env = |var: &str| match var {
+            LOCALE_ENV => Some("fr".to_string()),
+            START_TIME_ENV => Some("07:15".to_string()),
+            _ => None,
+        };
+        let overrides = Overrides {
+            locale: Some(Locale::En),
+            ..Overrides::default()
+        };
+        let settings = Settings::resolve(&overrides, env, &search).unwrap();
+        assert_eq!(settings.locale.value, Locale::En);
+        assert_eq!(
+            settings.start_time.value.format("%H:%M").to_string(),
+            "07:15"
+        );
+
+        let error = Settings::resolve(&Overrides::default(), env, &search).unwrap_err();
+        assert_eq!(
+            error,
+            "env TODOS_LOCALE: unsupported locale 'fr', expected es or en"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_config_is_an_error() {
+        let dir = temp_dir("invalid");
+        fs::write(dir.join(LOCAL_CONFIG_FILE), "unknown_key = 1\n").unwrap();
+        let search = SearchPaths {
+            local_dir: dir.clone(),
+            config_dir: None,
+        };
+        assert!(Settings::resolve(&Overrides::default(), no_env, &search).is_err());
+
+        fs::write(dir.join(LOCAL_CONFIG_FILE), "start_time = \"9am\"\n").unwrap();
+        assert!(Settings::resolve(&Overrides::default(), no_env, &search).is_err());
+
+        fs::write(dir.join(LOCAL_CONFIG_FILE), "layout = \"weekly\"\n").unwrap();
+        assert!(Settings::resolve(&Overrides::default(), no_env, &search).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Serialize;
+use tera::Context;
+
+use crate::Todos;
+
+/// English weekday names used in the `is_first_<weekday>_of_month` variables.
+const WEEKDAY_KEYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// A task of the catalogue as seen by the day templates.
+#[derive(Serialize)]
+struct Task<'a> {
+    priority: u8,
+    description: &'a str,
+    /// Start time, `HH:MM`.
+    time: Option<String>,
+}
+
+fn month_stem(year: i32, month: u32) -> String {
+    format!("TODOS - {:04}{:02}", year, month)
+}
+
+pub(crate) fn previous_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 1 {
+        (year - 1, 12)
+    } else {
+        (year, month - 1)
+    }
+}
+
+fn next_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
+/// Variables shared by the header and day templates: the period and the file names
+/// of the previous and next month, for wiki links.
+fn period_context(todos: &Todos, year: i32, month: u32) -> Context {
+    let mut context = Context::new();
+    let (prev_year, prev_month) = previous_month(year, month);
+    let (next_year, next_month) = next_month(year, month);
+    context.insert("YYYYMM", &format!("{:04}{:02}", year, month));
+    context.insert("PERIOD", &todos.period());
+    context.insert("year", &year);
+    context.insert("month", &month);
+    context.insert("month_name", todos.locale.month_name(month));
+    context.insert("prev_month", &month_stem(prev_year, prev_month));
+    context.insert("next_month", &month_stem(next_year, next_month));
+    context
+}
+
+/// Context of `header.md`.
+pub fn header_context(todos: &Todos) -> Context {
+    let mut context = period_context(todos, todos.year, todos.month);
+    if let Some(week) = todos.week {
+        context.insert("iso_week", &week);
+    }
+    context
+}
+
+/// Context of the day templates for `date`. `holiday` is only set on holidays,
+/// `profile` only on the days of a profile and `tasks` only with a task catalogue.
+pub fn day_context(todos: &Todos, date: NaiveDate) -> Context {
+    let mut context = period_context(todos, date.year(), date.month());
+    // Daily note names follow the configured pattern, or Obsidian's default one.
+    let notes = todos.daily_notes.clone().unwrap_or_default();
+    let weekday = date.weekday();
+    let tomorrow = date.succ_opt();
+
+    context.insert("YYYYMMDD", &date.format("%Y%m%d").to_string());
+    context.insert("date", &date.format("%Y-%m-%d").to_string());
+    context.insert("weekday", todos.locale.weekday_name(weekday));
+    context.insert("weekday_number", &weekday.number_from_monday());
+    context.insert("day", &date.day());
+    context.insert("iso_week", &date.iso_week().week());
+    context.insert("day_of_year", &date.ordinal());
+    context.insert(
+        "is_weekend",
+        &matches!(weekday, Weekday::Sat | Weekday::Sun),
+    );
+    for (index, key) in WEEKDAY_KEYS.iter().enumerate() {
+        let is_first = date.day() <= 7 && weekday.num_days_from_monday() as usize == index;
+        context.insert(format!("is_first_{}_of_month", key), &is_first);
+    }
+    context.insert(
+        "is_last_day_of_month",
+        &tomorrow.is_none_or(|next| next.month() != date.month()),
+    );
+    if let Some(holiday) = todos.holidays.lookup(date) {
+        context.insert("holiday", &holiday.name);
+    }
+    if let Some(profile) = todos.profiles.lookup(date) {
+        context.insert("profile", &profile.name);
+    }
+    if let Some(catalogue) = &todos.catalogue {
+        let tasks: Vec<Task> = catalogue
+            .on(date)
+            .into_iter()
+            .map(|task| Task {
+                priority: task.priority,
+                description: &task.description,
+                time: task.time.map(|time| time.format("%H:%M").to_string()),
+            })
+            .collect();
+        context.insert("tasks", &tasks);
+    }
+    if let Some(yesterday) = date.pred_opt() {
+        context.insert("prev_day", &notes.link(yesterday));
+    }
+    if let Some(tomorrow) = tomorrow {
+        context.insert("next_day", &notes.link(tomorrow));
+    }
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn value(context: &Context, key: &str) -> String {
+        context
+            .get(key)
+            .unwrap_or_else(|| panic!("{} is missing", key))
+            .to_string()
+    }
+
+    #[test]
+    fn test_day_context() {
+        let todos = Todos::new(2026, 8, PathBuf::from("."));
+        let context = day_context(&todos, NaiveDate::from_ymd_opt(2026, 8, 3).unwrap());
+        assert_eq!(value(&context, "date"), "\"2026-08-03\"");
+        assert_eq!(value(&context, "weekday"), "\"Lunes\"");
+        assert_eq!(value(&context, "day"), "3");
+        assert_eq!(value(&context, "iso_week"), "32");
+        assert_eq!(value(&context, "day_of_year"), "215");
+        assert_eq!(value(&context, "month_name"), "\"Agosto\"");
+        assert_eq!(value(&context, "is_weekend"), "false");
+        assert_eq!(value(&context, "is_first_monday_of_month"), "true");
+        assert_eq!(value(&context, "is_first_tuesday_of_month"), "false");
+        assert_eq!(value(&context, "is_last_day_of_month"), "false");
+        assert_eq!(value(&context, "prev_day"), "\"2026-08-02\"");
+        assert_eq!(value(&context, "next_day"), "\"2026-08-04\"");
+        assert_eq!(value(&context, "prev_month"), "\"TODOS - 202607\"");
+        assert_eq!(value(&context, "next_month"), "\"TODOS - 202609\"");
+    }
+
+    #[test]
+    fn test_day_context_at_month_and_year_ends() {
+        let todos = Todos::new(2026, 12, PathBuf::from("."));
+        let context = day_context(&todos, NaiveDate::from_ymd_opt(2026, 12, 31).unwrap());
+        assert_eq!(value(&context, "is_last_day_of_month"), "true");
+        assert_eq!(value(&context, "is_first_thursday_of_month"), "false");
+        assert_eq!(value(&context, "next_month"), "\"TODOS - 202701\"");
+        assert_eq!(value(&context, "next_day"), "\"2027-01-01\"");
+
+        let context = day_context(&todos, NaiveDate::from_ymd_opt(2026, 12, 5).unwrap());
+        assert_eq!(value(&context, "is_weekend"), "true");
+        assert_eq!(value(&context, "is_first_saturday_of_month"), "true");
+    }
+
+    #[test]
+    fn test_week_days_use_their_own_month() {
+        let todos = Todos::new_week(2026, 31, PathBuf::from("."));
+        let context = header_context(&todos);
+        assert_eq!(value(&context, "iso_week"), "31");
+        assert_eq!(value(&context, "PERIOD"), "\"2026-W31\"");
+
+        let context = day_context(&todos, NaiveDate::from_ymd_opt(2026, 8, 1).unwrap());
+        assert_eq!(value(&context, "YYYYMM"), "\"202608\"");
+        assert_eq!(value(&context, "day"), "1");
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The line was read with a fallback, e.g. a priority clamped to 6.
+    Warning,
+    /// The line was skipped.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A problem found on a line of a parsed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// File the line belongs to, `None` until the caller that read it sets it.
+    pub file: Option<PathBuf>,
+    /// Line number, from 1.
+    pub line: usize,
+    /// Column in characters, from 1.
+    pub column: usize,
+    pub severity: Severity,
+    pub message: String,
+    /// Text of the line, shown under the location.
+    pub source: String,
+}
+
+impl Diagnostic {
+    /// Diagnostic on line `line` whose text is `source`, pointing at byte `offset` of it.
+    pub fn new(
+        severity: Severity,
+        line: usize,
+        source: &str,
+        offset: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: None,
+            line,
+            column: source
+                .get(..offset)
+                .map_or(1, |text| text.chars().count() + 1),
+            severity,
+            message: message.into(),
+            source: source.to_string(),
+        }
+    }
+
+    pub fn in_file(mut self, path: &Path) -> Self {
+        self.file = Some(path.to_path_buf());
+        self
+    }
+}
+
+/// Sets the file of every diagnostic.
+pub fn in_file(diagnostics: Vec<Diagnostic>, path: &Path) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.in_file(path))
+        .collect()
+}
+
+/// Prints the diagnostic the way rustc does:
+///
+/// ```text
+/// warning: priority 9 outside 1 to 6, using 6
+///   --> TODOS - 202608.md:12:7
+///    |
+/// 12 | - [ ] 9. Llamar al banco
+///    |       ^
+/// ```
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self
+            .file
+            .as_ref()
+            .map_or(String::from("<input>"), |file| file.display().to_string());
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}: {}", self.severity, self.message)?;
+        writeln!(f, "{}--> {}:{}:{}", gutter, file, self.line, self.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_points_at_the_column() {
+        let source = "- [ ] 9. Llamar al banco";
+        let diagnostic = Diagnostic::new(
+            Severity::Warning,
+            12,
+            source,
+            6,
+            "priority 9 outside 1 to 6, using 6",
+        )
+        .in_file(Path::new("TODOS - 202608.md"));
+        assert_eq!(diagnostic.column, 7);
+        assert_eq!(
+            diagnostic.to_string(),
+            "warning: priority 9 outside 1 to 6, using 6\n  --> TODOS - 202608.md:12:7\n   |\n12 | - [ ] 9. Llamar al banco\n   |       ^"
+        );
+    }
+
+    #[test]
+    fn test_column_counts_characters() {
+        let source = "Miércoles,1,9am,30";
+        let diagnostic = Diagnostic::new(Severity::Error, 2, source, 13, "invalid hour");
+        assert_eq!(diagnostic.column, 13);
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::NaiveDate;
+
+use crate::Todos;
+
+/// Tags written when none are configured.
+pub const DEFAULT_TAGS: [&str; 1] = ["todos"];
+
+/// YAML frontmatter written at the top of generated TODOS files, for Dataview queries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frontmatter {
+    pub tags: Vec<String>,
+}
+
+impl Default for Frontmatter {
+    fn default() -> Self {
+        Self {
+            tags: DEFAULT_TAGS.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn list(values: &[String]) -> String {
+    let quoted: Vec<String> = values.iter().map(|value| quote(value)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+impl Frontmatter {
+    /// Frontmatter block of `todos`, closing `---` line included.
+    pub fn render(&self, todos: &Todos, planned: usize) -> String {
+        let days = todos.get_days();
+        let mut aliases = vec![format!("TODOS {}", todos.period())];
+        if todos.week.is_none() {
+            aliases.push(format!(
+                "{} {}",
+                todos.locale.month_name(todos.month),
+                todos.year
+            ));
+        }
+
+        let mut yaml = String::from("---\n");
+        yaml.push_str(&format!("year: {}\n", todos.year));
+        yaml.push_str(&format!("month: {}\n", todos.month));
+        if let Some(week) = todos.week {
+            yaml.push_str(&format!("week: {}\n", week));
+        }
+        if let (Some(start), Some(end)) = (days.first(), days.last()) {
+            yaml.push_str(&format!("start: {}\n", iso(*start)));
+            yaml.push_str(&format!("end: {}\n", iso(*end)));
+        }
+        yaml.push_str(&format!("tags: {}\n", list(&self.tags)));
+        yaml.push_str(&format!("aliases: {}\n", list(&aliases)));
+        yaml.push_str(&format!("planned: {}\n", planned));
+        yaml.push_str("---\n");
+        yaml
+    }
+}
+
+fn iso(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Returns `content` without its leading YAML frontmatter, if it has one.
+pub fn strip(content: &str) -> &str {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return content;
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == "---" {
+            return &rest[offset..];
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_render_month() {
+        let todos = Todos::new(2026, 8, PathBuf::from("."));
+        let frontmatter = Frontmatter {
+            tags: vec!["todos".to_string(), "ivy-lee".to_string()],
+        };
+        assert_eq!(
+            frontmatter.render(&todos, 155),
+            "---\nyear: 2026\nmonth: 8\nstart: 2026-08-01\nend: 2026-08-31\n\
+             tags: [\"todos\", \"ivy-lee\"]\naliases: [\"TODOS 202608\", \"Agosto 2026\"]\n\
+             planned: 155\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_render_week() {
+        let todos = Todos::new_week(2026, 31, PathBuf::from("."));
+        let yaml = Frontmatter::default().render(&todos, 35);
+        assert!(yaml.contains("week: 31\nstart: 2026-07-27\nend: 2026-08-02\n"));
+        assert!(yaml.contains("aliases: [\"TODOS 2026-W31\"]\n"));
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip("---\nyear: 2026\n---\n# TODOS\n"), "# TODOS\n");
+        assert_eq!(strip("# TODOS\n\n---\n"), "# TODOS\n\n---\n");
+        assert_eq!(strip("---\nunterminated\n"), "---\nunterminated\n");
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{Datelike, NaiveDate};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::diagnostics::{self, Diagnostic, Severity};
+
+/// Template used for holidays that do not name their own.
+pub const HOLIDAY_TEMPLATE: &str = "holiday.md";
+
+/// File names of the holidays list looked up inside a templates directory.
+pub const HOLIDAYS_FILES: [&str; 2] = ["holidays.csv", "holidays.ics"];
+
+/// Day a holiday falls on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolidayDate {
+    /// A single date, e.g. `2026-04-03`.
+    Exact(NaiveDate),
+    /// The same month and day every year, e.g. `12-25`.
+    Yearly { month: u32, day: u32 },
+}
+
+impl HolidayDate {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+            return Some(HolidayDate::Exact(date));
+        }
+        let (month, day) = text.split_once('-')?;
+        let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+        // 2024 is a leap year, so `02-29` is accepted.
+        NaiveDate::from_ymd_opt(2024, month, day)?;
+        Some(HolidayDate::Yearly { month, day })
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        match *self {
+            HolidayDate::Exact(exact) => exact == date,
+            HolidayDate::Yearly { month, day } => date.month() == month && date.day() == day,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: HolidayDate,
+    pub name: String,
+    /// Template rendering the day, e.g. `holiday.md`.
+    pub template: String,
+}
+
+/// Days rendered from an alternate template instead of their weekday template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Holidays(pub Vec<Holiday>);
+
+impl Holidays {
+    /// Holiday on `date`. Exact dates win over yearly ones.
+    pub fn lookup(&self, date: NaiveDate) -> Option<&Holiday> {
+        let on_date = || self.0.iter().filter(|holiday| holiday.date.matches(date));
+        on_date()
+            .find(|holiday| matches!(holiday.date, HolidayDate::Exact(_)))
+            .or_else(|| on_date().next())
+    }
+
+    pub fn parse_csv(content: &str) -> Self {
+        Self::parse_csv_with_diagnostics(content).0
+    }
+
+    /// Parses a `date,name,template` CSV with a header row, with the problems found in
+    /// its lines. Dates are `YYYY-MM-DD` or yearly `MM-DD`; an empty template means
+    /// `holiday.md`. Malformed lines are skipped.
+    pub fn parse_csv_with_diagnostics(content: &str) -> (Self, Vec<Diagnostic>) {
+        let mut holidays = Vec::new();
+        let mut diagnostics = Vec::new();
+        for (index, line) in content.lines().enumerate().skip(1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut report = |message: String| {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    index + 1,
+                    line,
+                    0,
+                    message,
+                ))
+            };
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [date, name, rest @ ..] = parts.as_slice() else {
+                report(String::from(
+                    "expected date,name and an optional template, skipped",
+                ));
+                continue;
+            };
+            let Some(date) = HolidayDate::parse(date) else {
+                report(format!(
+                    "invalid date '{}', expected YYYY-MM-DD or MM-DD, skipped",
+                    date
+                ));
+                continue;
+            };
+            let template = rest
+                .first()
+                .filter(|template| !template.is_empty())
+                .map(|template| template.to_string())
+                .unwrap_or_else(|| HOLIDAY_TEMPLATE.to_string());
+            holidays.push(Holiday {
+                date,
+                name: name.to_string(),
+                template,
+            });
+        }
+        (Holidays(holidays), diagnostics)
+    }
+
+    /// Parses the all-day events of an ICS calendar, e.g. a public holidays feed.
+    /// Every day from `DTSTART` up to the exclusive `DTEND` uses `holiday.md`.
+    pub fn parse_ics(content: &str) -> Self {
+        let unfolded = content.replace("\r\n ", "").replace("\n ", "");
+        let mut holidays = Vec::new();
+        let (mut start, mut end, mut name) = (None, None, String::new());
+        for line in unfolded.lines() {
+            let line = line.trim_end_matches('\r');
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let property = key.split(';').next().unwrap_or_default();
+            let date = || NaiveDate::parse_from_str(value.get(..8).unwrap_or(value), "%Y%m%d").ok();
+            match (property, value) {
+                ("BEGIN", "VEVENT") => (start, end, name) = (None, None, String::new()),
+                ("DTSTART", _) => start = date(),
+                ("DTEND", _) => end = date(),
+                ("SUMMARY", _) => name = value.replace("\\,", ",").replace("\\;", ";"),
+                ("END", "VEVENT") => {
+                    let Some(first) = start else { continue };
+                    // A missing or non-increasing DTEND makes a single-day event.
+                    let last = end
+                        .and_then(|end| end.pred_opt())
+                        .unwrap_or(first)
+                        .max(first);
+                    for date in first.iter_days().take_while(|date| *date <= last) {
+                        holidays.push(Holiday {
+                            date: HolidayDate::Exact(date),
+                            name: name.clone(),
+                            template: HOLIDAY_TEMPLATE.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Holidays(holidays)
+    }
+
+    /// Reads a holidays list, as ICS when the file ends in `.ics` and as CSV otherwise.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(Self::load_with_diagnostics(path)?.0)
+    }
+
+    /// Reads a CSV or ICS holidays list, with the problems found in the CSV lines.
+    pub fn load_with_diagnostics(path: &Path) -> io::Result<(Self, Vec<Diagnostic>)> {
+        let content = fs::read_to_string(path)?;
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"))
+        {
+            Ok((Self::parse_ics(&content), Vec::new()))
+        } else {
+            let (holidays, diagnostics) = Self::parse_csv_with_diagnostics(&content);
+            Ok((holidays, diagnostics::in_file(diagnostics, path)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let (holidays, diagnostics) = Holidays::parse_csv_with_diagnostics(
+            "date,name,template\n12-25,Navidad,\n2026-12-25,Navidad 2026,familia.md\n2026-09-16,Independencia\n2026-13-01,Nada,\n\nsolo\n",
+        );
+        assert_eq!(holidays.0.len(), 3);
+        let found: Vec<(usize, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    5,
+                    "invalid date '2026-13-01', expected YYYY-MM-DD or MM-DD, skipped"
+                ),
+                (7, "expected date,name and an optional template, skipped"),
+            ]
+        );
+        assert_eq!(
+            holidays.lookup(date(2027, 12, 25)).map(|h| h.name.as_str()),
+            Some("Navidad")
+        );
+        let christmas = holidays.lookup(date(2026, 12, 25)).unwrap();
+        assert_eq!(christmas.name, "Navidad 2026");
+        assert_eq!(christmas.template, "familia.md");
+        let independence = holidays.lookup(date(2026, 9, 16)).unwrap();
+        assert_eq!(independence.template, HOLIDAY_TEMPLATE);
+        assert_eq!(holidays.lookup(date(2026, 9, 17)), None);
+    }
+
+    #[test]
+    fn test_parse_ics_all_day_events() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261225\r\nDTEND;VALUE=DATE:20261226\r\nSUMMARY:Navidad\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20260803\r\nDTEND;VALUE=DATE:20260806\r\nSUMMARY:Viaje\\, playa\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let holidays = Holidays::parse_ics(ics);
+        assert_eq!(holidays.0.len(), 4);
+        assert_eq!(holidays.lookup(date(2026, 12, 25)).unwrap().name, "Navidad");
+        assert_eq!(
+            holidays.lookup(date(2026, 8, 5)).unwrap().name,
+            "Viaje, playa"
+        );
+        assert_eq!(holidays.lookup(date(2026, 8, 6)), None);
+    }
+}
@@ -1,24 +1,161 @@
 pub mod calendar;
+pub mod catalogue;
+pub mod config;
+pub mod context;
+pub mod diagnostics;
+pub mod frontmatter;
+pub mod holidays;
+pub mod lint;
+pub mod locale;
+pub mod merge;
+pub mod output;
 pub mod parser;
+pub mod profiles;
+pub mod recurrence;
+pub mod templates;
 
 use chrono::Datelike;
+use chrono::Days;
 use chrono::NaiveDate;
+use chrono::Weekday;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tera::{Context, Tera};
+use std::path::{Component, Path, PathBuf};
+
+use crate::calendar::{IcsOptions, generate_ics_with};
+use crate::catalogue::TaskCatalogue;
+use crate::diagnostics::Diagnostic;
+use crate::frontmatter::Frontmatter;
+use crate::holidays::Holidays;
+use crate::locale::{FileKind, LOCALES, Locale};
+use crate::merge::{MergeReport, merge_month};
+use crate::output::{DailyNotes, OverwriteMode, check_distinct, report, write_file};
+use crate::parser::{DueTimeRule, MdParser, TodoItem};
+use crate::profiles::Profiles;
+use crate::recurrence::RecurringTasks;
 
 pub struct Todos {
     // Add fields as needed, e.g. year, month, days, etc.
     pub year: i32,
     pub month: u32,
+    /// ISO week number when the file plans a single week; `year` is then the ISO week-year.
+    pub week: Option<u32>,
     pub path: PathBuf,
+    pub overwrite: OverwriteMode,
+    /// Directory whose templates override the built-in ones.
+    pub templates_dir: Option<PathBuf>,
+    pub locale: Locale,
+    /// Write one note per day plus an index note instead of a single file.
+    pub daily_notes: Option<DailyNotes>,
+    /// YAML frontmatter written before the header.
+    pub frontmatter: Option<Frontmatter>,
+    /// Days rendered from a holiday template instead of their weekday template.
+    pub holidays: Holidays,
+    /// Date ranges whose days are rendered from the templates of a profile.
+    pub profiles: Profiles,
+    /// Tasks added to the days their recurrence matches.
+    pub recurring: RecurringTasks,
+    /// Task catalogue rendering the days instead of the weekday templates.
+    pub catalogue: Option<TaskCatalogue>,
+    /// Open items of the previous period, planned again on the first day.
+    pub carried: Vec<TodoItem>,
+    /// Directory keeping the merge bases, `None` to keep none.
+    pub state_dir: Option<PathBuf>,
 }
 
 impl Todos {
     pub fn new(year: i32, month: u32, path: PathBuf) -> Self {
-        Self { year, month, path }
+        Self {
+            year,
+            month,
+            week: None,
+            path,
+            overwrite: OverwriteMode::default(),
+            templates_dir: None,
+            locale: Locale::default(),
+            daily_notes: None,
+            frontmatter: None,
+            holidays: Holidays::default(),
+            profiles: Profiles::default(),
+            recurring: RecurringTasks::default(),
+            catalogue: None,
+            carried: Vec::new(),
+            state_dir: None,
+        }
+    }
+
+    /// Todos for ISO week `week` of the ISO week-year `year`, from Monday to Sunday.
+    /// `month` is the month of the Monday.
+    pub fn new_week(year: i32, week: u32, path: PathBuf) -> Self {
+        let month =
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).map_or(1, |monday| monday.month());
+        Self {
+            week: Some(week),
+            ..Self::new(year, month, path)
+        }
+    }
+
+    pub fn with_state_dir(mut self, state_dir: Option<PathBuf>) -> Self {
+        self.state_dir = state_dir;
+        self
+    }
+
+    pub fn with_carried(mut self, carried: Vec<TodoItem>) -> Self {
+        self.carried = carried;
+        self
+    }
+
+    pub fn with_catalogue(mut self, catalogue: Option<TaskCatalogue>) -> Self {
+        self.catalogue = catalogue;
+        self
+    }
+
+    pub fn with_recurring(mut self, recurring: RecurringTasks) -> Self {
+        self.recurring = recurring;
+        self
+    }
+
+    pub fn with_profiles(mut self, profiles: Profiles) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    pub fn with_holidays(mut self, holidays: Holidays) -> Self {
+        self.holidays = holidays;
+        self
+    }
+
+    pub fn with_frontmatter(mut self, frontmatter: Option<Frontmatter>) -> Self {
+        self.frontmatter = frontmatter;
+        self
+    }
+
+    pub fn with_daily_notes(mut self, daily_notes: Option<DailyNotes>) -> Self {
+        self.daily_notes = daily_notes;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn with_templates_dir(mut self, templates_dir: Option<PathBuf>) -> Self {
+        self.templates_dir = templates_dir;
+        self
+    }
+
+    pub fn with_overwrite(mut self, overwrite: OverwriteMode) -> Self {
+        self.overwrite = overwrite;
+        self
     }
+
     pub fn get_days(&self) -> Vec<chrono::NaiveDate> {
+        if let Some(week) = self.week {
+            return NaiveDate::from_isoywd_opt(self.year, week, Weekday::Mon)
+                .map(|monday| monday.iter_days().take(7).collect())
+                .unwrap_or_default();
+        }
         let days_in_month = match self.month {
             1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
             4 | 6 | 9 | 11 => 30,
@@ -38,46 +175,411 @@ impl Todos {
             .filter_map(|day| NaiveDate::from_ymd_opt(self.year, self.month, day))
             .collect()
     }
+
+    /// Planned period, e.g. `202608` for a month or `2026-W33` for a week.
+    pub fn period(&self) -> String {
+        match self.week {
+            Some(week) => format!("{:04}-W{:02}", self.year, week),
+            None => format!("{:04}{:02}", self.year, self.month),
+        }
+    }
+
+    /// File name without extension, e.g. `TODOS - 202608` or `TODOS - 2026-W33`.
+    pub fn stem(&self) -> String {
+        format!("TODOS - {}", self.period())
+    }
+
+    /// TODOS file named `stem`, e.g. `TODOS - 202608` or `TODOS - 2026-W33`.
+    pub fn from_stem(stem: &str, path: PathBuf) -> Option<Self> {
+        let period = stem.strip_prefix("TODOS - ")?;
+        if let Some((year, week)) = period.split_once("-W") {
+            let (year, week) = (year.parse().ok()?, week.parse().ok()?);
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+            return Some(Self::new_week(year, week, path));
+        }
+        if period.len() != 6 || !period.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let (year, month) = (period[..4].parse().ok()?, period[4..].parse().ok()?);
+        (1..=12)
+            .contains(&month)
+            .then(|| Self::new(year, month, path))
+    }
+
+    /// TODOS file of the previous month or week, in the same directory.
+    pub fn previous_md_path(&self) -> PathBuf {
+        let previous_week = self.week.and_then(|_| {
+            let monday = self.get_days().first()?.checked_sub_days(Days::new(7))?;
+            let week = monday.iso_week();
+            Some(Todos::new_week(week.year(), week.week(), self.path.clone()))
+        });
+        let previous = previous_week.unwrap_or_else(|| {
+            let (year, month) = context::previous_month(self.year, self.month);
+            Todos::new(year, month, self.path.clone())
+        });
+        previous.md_path()
+    }
+
+    pub fn md_path(&self) -> PathBuf {
+        self.path.join(format!("{}.md", self.stem()))
+    }
+
+    pub fn ics_path(&self) -> PathBuf {
+        self.path.join(format!("{}.ics", self.stem()))
+    }
+
+    /// Daily note of `date` when writing daily notes, e.g. `Daily/2026-08-01.md`.
+    pub fn daily_note_path(&self, date: NaiveDate) -> Option<PathBuf> {
+        self.daily_notes
+            .as_ref()
+            .map(|notes| self.path.join(format!("{}.md", notes.link(date))))
+    }
+
+    /// Every markdown file written for the period: the TODOS file and any daily notes.
+    pub fn md_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.md_path()];
+        paths.extend(
+            self.get_days()
+                .into_iter()
+                .filter_map(|date| self.daily_note_path(date)),
+        );
+        paths
+    }
+
+    /// Pristine copy of the last generated markdown, used as merge base. It is kept in
+    /// the state directory under the absolute output path, out of the output directory,
+    /// e.g. `bases/home/me/vault/TODOS - 202608.base.md`.
+    pub fn base_path(&self) -> Option<PathBuf> {
+        let state_dir = self.state_dir.as_ref()?;
+        let output = fs::canonicalize(&self.path)
+            .or_else(|_| std::path::absolute(&self.path))
+            .ok()?;
+        let mut base = state_dir.join("bases");
+        for component in output.components() {
+            match component {
+                Component::Normal(name) => base.push(name),
+                // A Windows drive, e.g. `C:`.
+                Component::Prefix(prefix) => base.push(
+                    prefix
+                        .as_os_str()
+                        .to_string_lossy()
+                        .replace(|c: char| !c.is_ascii_alphanumeric(), ""),
+                ),
+                _ => {}
+            }
+        }
+        Some(base.join(format!("{}.base.md", self.stem())))
+    }
 }
 
-pub fn create_todos_file(todos: &Todos) -> Result<(), Box<dyn std::error::Error>> {
-    let filename = format!("TODOS - {:04}{:02}.md", todos.year, todos.month);
+/// Parses a `YYYY-MM` month, as given to `--from` and `--to`.
+pub fn parse_year_month(text: &str) -> Result<(i32, u32), String> {
+    let invalid = || format!("invalid month '{}', expected YYYY-MM", text);
+    let (year, month) = text.trim().split_once('-').ok_or_else(invalid)?;
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+    if year.to_string().len() != 4 || !(1..=12).contains(&month) {
+        return Err(invalid());
+    }
+    Ok((year, month))
+}
 
-    let output_path = todos.path.clone().join(&filename);
+/// Every `(year, month)` from `from` to `to`, both included. Empty when `to` is before `from`.
+pub fn months_between(from: (i32, u32), to: (i32, u32)) -> Vec<(i32, u32)> {
+    let mut months = Vec::new();
+    let (mut year, mut month) = from;
+    while (year, month) <= to {
+        months.push((year, month));
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    months
+}
 
-    let tera = Tera::new("templates/*.md")?;
+/// File name without extension of a file covering several months, e.g. `TODOS - 202701-202712`.
+pub fn range_stem(from: (i32, u32), to: (i32, u32)) -> String {
+    format!("TODOS - {:04}{:02}-{:04}{:02}", from.0, from.1, to.0, to.1)
+}
 
-    let mut context = Context::new();
-    let yyyymm = format!("{:04}{:02}", todos.year, todos.month);
+/// Rendered header and days of a month or week.
+struct Rendered {
+    header: String,
+    days: Vec<(NaiveDate, String)>,
+}
 
-    context.insert("YYYYMM", &yyyymm);
+/// Renders the header and each day of the month or week from the templates.
+fn render_parts(todos: &Todos) -> Result<Rendered, Box<dyn std::error::Error>> {
+    let tera = templates::load_tera(todos.templates_dir.as_deref())?;
+    // Profiles with their own templates directory render from a separate engine.
+    let mut profile_teras = HashMap::new();
+    for dir in todos
+        .profiles
+        .0
+        .iter()
+        .filter_map(|p| p.templates_dir.as_deref())
+    {
+        if !profile_teras.contains_key(dir) {
+            profile_teras.insert(dir, templates::load_tera(Some(dir))?);
+        }
+    }
 
-    let mut content = tera.render("header.md", &context)?;
+    let mut header = tera.render("header.md", &context::header_context(todos))?;
 
+    let mut days = Vec::new();
     for date in todos.get_days() {
-        let mut day_ctx = Context::new();
-        let yyyymmdd = date.format("%Y%m%d").to_string();
-        day_ctx.insert("YYYYMMDD", &yyyymmdd);
-        let weekday = date.weekday().number_from_monday();
-        let template_name = format!("{}.md", weekday);
-        let day_content = tera.render(&template_name, &day_ctx)?;
+        let holiday = todos.holidays.lookup(date);
+        let profile = todos.profiles.lookup(date);
+        let tera = profile
+            .and_then(|p| p.templates_dir.as_deref())
+            .and_then(|dir| profile_teras.get(dir))
+            .unwrap_or(&tera);
+        let template_name =
+            templates::day_template(tera, date, holiday, profile, todos.catalogue.is_some());
+        let day_content = tera.render(&template_name, &context::day_context(todos, date))?;
+        days.push((date, todos.recurring.insert(date, &day_content)));
+    }
+
+    let carried = carried_tasks(todos, &days_items(&days));
+    if let Some((_, first_day)) = days.first_mut() {
+        *first_day = MdParser::insert_tasks(first_day, &carried);
+    }
+
+    if let Some(frontmatter) = &todos.frontmatter {
+        let planned = open_count(&days_items(&days));
+        header.insert_str(0, &frontmatter.render(todos, planned));
+    }
+
+    Ok(Rendered { header, days })
+}
+
+fn days_items(days: &[(NaiveDate, String)]) -> Vec<TodoItem> {
+    days.iter()
+        .flat_map(|(_, content)| MdParser::parse(content))
+        .collect()
+}
+
+fn open_count(items: &[TodoItem]) -> usize {
+    items.iter().filter(|item| item.status.is_open()).count()
+}
+
+/// Description of a carried over item without the note with its original date.
+fn without_carried_note(description: &str) -> &str {
+    description
+        .rsplit_once(" (")
+        .filter(|(_, note)| {
+            LOCALES
+                .iter()
+                .any(|locale| note.starts_with(locale.carried_from()))
+        })
+        .map_or(description, |(description, _)| description)
+}
+
+/// Carried over items to add to the first day, each noting its original date.
+/// Tasks already planned in the period, e.g. from the weekday templates, are left
+/// out, and a task on several days is carried once if it is open on its latest day.
+fn carried_tasks(todos: &Todos, planned: &[TodoItem]) -> Vec<(u8, String)> {
+    let mut tasks: Vec<(u8, String)> = Vec::new();
+    let mut seen: Vec<&str> = planned
+        .iter()
+        .map(|item| without_carried_note(&item.description))
+        .collect();
+    for item in todos.carried.iter().rev() {
+        let description = without_carried_note(&item.description);
+        if seen.contains(&description) {
+            continue;
+        }
+        seen.push(description);
+        if !item.status.is_open() {
+            continue;
+        }
+        let task = if description.len() < item.description.len() {
+            item.description.clone()
+        } else {
+            format!(
+                "{} ({} {})",
+                item.description,
+                todos.locale.carried_from(),
+                item.date.format("%Y%m%d")
+            )
+        };
+        tasks.push((item.priority, task));
+    }
+    tasks.reverse();
+    tasks
+}
+
+/// Renders the whole TODOS markdown for the month or week from the templates.
+pub fn render_todos(todos: &Todos) -> Result<String, Box<dyn std::error::Error>> {
+    let Rendered {
+        header: mut content,
+        days,
+    } = render_parts(todos)?;
+    for (_, day_content) in days {
         content.push_str(&day_content);
         content.push('\n');
     }
+    Ok(content)
+}
+
+pub fn create_todos_file(todos: &Todos) -> Result<(), Box<dyn std::error::Error>> {
+    if todos.daily_notes.is_some() {
+        return create_daily_notes(todos);
+    }
 
-    if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent)?;
+    let output_path = todos.md_path();
+    let content = render_todos(todos)?;
+
+    let outcome = write_file(&output_path, &content, todos.overwrite)?;
+    if let Some(base_path) = todos.base_path() {
+        write_file(&base_path, &content, OverwriteMode::Force)?;
+    }
+    report(todos.locale, FileKind::Todos, &output_path, &outcome);
+    Ok(())
+}
+
+/// Writes each rendered day to its own daily note and the header, followed by a link
+/// to every day, to the TODOS file as index note.
+fn create_daily_notes(todos: &Todos) -> Result<(), Box<dyn std::error::Error>> {
+    check_distinct(&todos.md_paths())?;
+    let Rendered {
+        header: mut index,
+        days,
+    } = render_parts(todos)?;
+    for (date, content) in days {
+        let (Some(notes), Some(path)) = (&todos.daily_notes, todos.daily_note_path(date)) else {
+            unreachable!("daily notes are configured");
+        };
+        let outcome = write_file(&path, &content, todos.overwrite)?;
+        report(todos.locale, FileKind::DailyNote, &path, &outcome);
+        index.push_str(&format!(
+            "- [[{}|{} - {}]]\n",
+            notes.link(date),
+            date.format("%Y%m%d"),
+            todos.locale.weekday_name(date.weekday())
+        ));
     }
-    fs::write(&output_path, content)?;
 
-    println!("Archivo TODOS creado: {}", output_path.display());
+    let output_path = todos.md_path();
+    let outcome = write_file(&output_path, &index, todos.overwrite)?;
+    report(todos.locale, FileKind::Todos, &output_path, &outcome);
     Ok(())
 }
 
+/// Parses the todo items of a TODOS file, including those of the daily notes it links to.
+pub fn read_todo_items(md_path: &Path) -> std::io::Result<Vec<TodoItem>> {
+    Ok(read_todo_items_with_diagnostics(md_path)?.0)
+}
+
+/// Like `read_todo_items`, with the problems found in the TODOS file and its daily notes.
+pub fn read_todo_items_with_diagnostics(
+    md_path: &Path,
+) -> std::io::Result<(Vec<TodoItem>, Vec<Diagnostic>)> {
+    let content = fs::read_to_string(md_path)?;
+    let (mut items, diagnostics) = MdParser::parse_with_diagnostics(&content);
+    let mut diagnostics = diagnostics::in_file(diagnostics, md_path);
+    let dir = md_path.parent().unwrap_or(Path::new(""));
+    for link in MdParser::daily_note_links(&content) {
+        let note_path = dir.join(format!("{}.md", link.target));
+        match fs::read_to_string(&note_path) {
+            Ok(note) => {
+                let (note_items, note_diagnostics) =
+                    MdParser::parse_daily_note_with_diagnostics(&link, &note);
+                items.extend(note_items);
+                diagnostics.extend(diagnostics::in_file(note_diagnostics, &note_path));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((items, diagnostics))
+}
+
+/// Re-renders the month from the current templates and merges it into the existing file.
+///
+/// Days up to `today` and days with checked items are kept as they are. Future days take
+/// the new template content unless they were edited by hand since the last generation,
+/// in which case the edits are kept and the day is reported as a conflict when the
+/// templates changed it too.
+pub fn merge_todos_file(
+    todos: &Todos,
+    today: NaiveDate,
+) -> Result<MergeReport, Box<dyn std::error::Error>> {
+    if todos.daily_notes.is_some() {
+        return Err("--merge is not supported with daily notes".into());
+    }
+    let output_path = todos.md_path();
+    let ours = fs::read_to_string(&output_path)?;
+    let base = todos
+        .base_path()
+        .and_then(|path| fs::read_to_string(path).ok());
+    let theirs = render_todos(todos)?;
+
+    let (mut content, merge_report) = merge_month(base.as_deref(), &ours, &theirs, today);
+    if let Some(frontmatter) = &todos.frontmatter {
+        let planned = open_count(&MdParser::parse(&content));
+        content = frontmatter.render(todos, planned) + frontmatter::strip(&content);
+    }
+
+    let outcome = write_file(&output_path, &content, todos.overwrite)?;
+    if let Some(base_path) = todos.base_path() {
+        write_file(&base_path, &theirs, OverwriteMode::Force)?;
+    }
+    report(todos.locale, FileKind::Todos, &output_path, &outcome);
+    Ok(merge_report)
+}
+
+/// Parses an existing TODOS markdown file as it is now and (re)writes its ICS calendar.
+/// Returns the problems found while parsing it.
+pub fn create_ics_file(
+    md_path: &Path,
+    ics_path: &Path,
+    name: &str,
+    rules: &[DueTimeRule],
+    options: &IcsOptions,
+    overwrite: OverwriteMode,
+    locale: Locale,
+) -> Result<Vec<Diagnostic>, Box<dyn std::error::Error>> {
+    create_combined_ics_file(
+        &[md_path.to_path_buf()],
+        ics_path,
+        name,
+        rules,
+        options,
+        overwrite,
+        locale,
+    )
+}
+
+/// Parses several TODOS markdown files and writes all their events to a single ICS calendar.
+pub fn create_combined_ics_file(
+    md_paths: &[PathBuf],
+    ics_path: &Path,
+    name: &str,
+    rules: &[DueTimeRule],
+    options: &IcsOptions,
+    overwrite: OverwriteMode,
+    locale: Locale,
+) -> Result<Vec<Diagnostic>, Box<dyn std::error::Error>> {
+    let mut items = Vec::new();
+    let mut diagnostics = Vec::new();
+    for md_path in md_paths {
+        let (file_items, file_diagnostics) = read_todo_items_with_diagnostics(md_path)?;
+        items.extend(file_items);
+        diagnostics.extend(file_diagnostics);
+    }
+    let ics_content = generate_ics_with(name, &items, rules, options);
+    let outcome = write_file(ics_path, &ics_content, overwrite)?;
+
+    report(locale, FileKind::Ics, ics_path, &outcome);
+    Ok(diagnostics)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_get_days_31_days_month() {
@@ -168,6 +670,71 @@ mod tests {
         assert!(days.is_empty());
     }
 
+    #[test]
+    fn test_get_days_iso_week_spans_months() {
+        let todos = Todos::new_week(2026, 31, PathBuf::from("."));
+        let days = todos.get_days();
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[0], NaiveDate::from_ymd_opt(2026, 7, 27).unwrap());
+        assert_eq!(days[6], NaiveDate::from_ymd_opt(2026, 8, 2).unwrap());
+        assert_eq!(todos.month, 7);
+        assert_eq!(todos.stem(), "TODOS - 2026-W31");
+
+        // 2027-W01 starts on Monday 2027-01-04, 2027-W53 does not exist.
+        let days = Todos::new_week(2027, 1, PathBuf::from(".")).get_days();
+        assert_eq!(days[0], NaiveDate::from_ymd_opt(2027, 1, 4).unwrap());
+        assert!(
+            Todos::new_week(2027, 53, PathBuf::from("."))
+                .get_days()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_render_week_uses_period_header() {
+        let todos = Todos::new_week(2026, 33, PathBuf::from("."));
+        let content = render_todos(&todos).unwrap();
+        assert!(content.starts_with("# TODOS 2026-W33\n"));
+        assert!(content.contains("## 20260810 - Lunes\n"));
+        assert!(content.contains("## 20260816 - Domingo\n"));
+        assert_eq!(content.matches("\n## ").count(), 7);
+    }
+
+    #[test]
+    fn test_output_paths() {
+        let todos = Todos::new(2026, 8, PathBuf::from("/tmp/todos"));
+        assert_eq!(todos.stem(), "TODOS - 202608");
+        assert_eq!(
+            todos.md_path(),
+            PathBuf::from("/tmp/todos/TODOS - 202608.md")
+        );
+        assert_eq!(
+            todos.ics_path(),
+            PathBuf::from("/tmp/todos/TODOS - 202608.ics")
+        );
+    }
+
+    #[test]
+    fn test_parse_year_month() {
+        assert_eq!(parse_year_month("2027-01"), Ok((2027, 1)));
+        assert_eq!(parse_year_month("2027-12"), Ok((2027, 12)));
+        assert!(parse_year_month("2027-13").is_err());
+        assert!(parse_year_month("2027").is_err());
+        assert!(parse_year_month("27-01").is_err());
+    }
+
+    #[test]
+    fn test_months_between_crosses_years() {
+        assert_eq!(
+            months_between((2026, 11), (2027, 2)),
+            vec![(2026, 11), (2026, 12), (2027, 1), (2027, 2)]
+        );
+        assert_eq!(months_between((2027, 1), (2027, 12)).len(), 12);
+        assert_eq!(months_between((2027, 5), (2027, 5)), vec![(2027, 5)]);
+        assert!(months_between((2027, 5), (2027, 4)).is_empty());
+        assert_eq!(range_stem((2027, 1), (2027, 12)), "TODOS - 202701-202712");
+    }
+
     #[test]
     fn test_create_todos_file() {
         let todos = Todos::new(2024, 2, PathBuf::from("."));
@@ -229,4 +796,180 @@ mod tests {
         // Clean up
         fs::remove_file(expected_file).unwrap();
     }
+
+    #[test]
+    fn test_merge_todos_file_keeps_checked_and_past_days() {
+        let dir = std::env::temp_dir().join(format!("todos-lib-merge-{}", std::process::id()));
+        let state = std::env::temp_dir().join(format!("todos-lib-state-{}", std::process::id()));
+        let todos = Todos::new(2026, 8, dir.clone()).with_state_dir(Some(state.clone()));
+        create_todos_file(&todos).unwrap();
+        let base = todos.base_path().unwrap();
+        assert!(base.starts_with(&state) && base.exists());
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            1,
+            "only the TODOS file"
+        );
+
+        let md_path = todos.md_path();
+        let edited = fs::read_to_string(&md_path).unwrap().replace(
+            "## 20260801 - Sábado\n\n- [ ] 1. [[Ejercicio]]",
+            "## 20260801 - Sábado\n\n- [x] 1. [[Ejercicio]]",
+        );
+        fs::write(&md_path, &edited).unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2026, 8, 10).unwrap();
+        let todos = todos.with_overwrite(OverwriteMode::Force);
+        let report = merge_todos_file(&todos, today).unwrap();
+
+        assert!(report.conflicts().is_empty());
+        assert_eq!(fs::read_to_string(&md_path).unwrap(), edited);
+        assert!(base.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&state).unwrap();
+    }
+
+    #[test]
+    fn test_render_todos_uses_locale_weekday_names() {
+        let todos = Todos::new(2024, 2, PathBuf::from(".")).with_locale(Locale::En);
+        let content = render_todos(&todos).unwrap();
+        assert!(content.contains("## 20240201 - Thursday\n"));
+        assert!(content.contains("## 20240207 - Wednesday\n"));
+        assert!(!content.contains("Jueves"));
+    }
+
+    #[test]
+    fn test_daily_notes_are_read_back_through_the_index() {
+        let dir = std::env::temp_dir().join(format!("todos-lib-daily-{}", std::process::id()));
+        let todos = Todos::new(2026, 8, dir.clone())
+            .with_daily_notes(Some(DailyNotes {
+                folder: PathBuf::from("Diario"),
+                ..DailyNotes::default()
+            }))
+            .with_state_dir(Some(dir.join("state")));
+        create_todos_file(&todos).unwrap();
+
+        let note = dir.join("Diario").join("2026-08-01.md");
+        assert!(
+            fs::read_to_string(&note)
+                .unwrap()
+                .starts_with("## 20260801 - Sábado")
+        );
+        assert_eq!(todos.md_paths().len(), 32);
+        let index = fs::read_to_string(todos.md_path()).unwrap();
+        assert!(index.starts_with("# TODOS 202608"));
+        assert!(index.contains("- [[Diario/2026-08-31|20260831 - Lunes]]\n"));
+        assert!(!todos.base_path().unwrap().exists());
+
+        let single = render_todos(&Todos::new(2026, 8, dir.clone())).unwrap();
+        let items = read_todo_items(&todos.md_path()).unwrap();
+        assert_eq!(items, MdParser::parse(&single));
+
+        fs::write(&note, "- [ ] 1. Nota sin encabezado\n").unwrap();
+        let items = read_todo_items(&todos.md_path()).unwrap();
+        assert_eq!(items[0].description, "Nota sin encabezado");
+        assert_eq!(items[0].date, NaiveDate::from_ymd_opt(2026, 8, 1).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_frontmatter_counts_planned_items_and_is_skipped_by_the_parser() {
+        let todos = Todos::new(2026, 8, PathBuf::from("."));
+        let plain = render_todos(&todos).unwrap();
+        let todos = todos.with_frontmatter(Some(Frontmatter::default()));
+        let content = render_todos(&todos).unwrap();
+
+        let planned = MdParser::parse(&plain).len();
+        assert!(content.starts_with("---\nyear: 2026\nmonth: 8\n"));
+        assert!(content.contains(&format!("planned: {}\n---\n# TODOS 202608", planned)));
+        assert_eq!(frontmatter::strip(&content), plain);
+        assert_eq!(MdParser::parse(&content), MdParser::parse(&plain));
+    }
+
+    #[test]
+    fn test_carried_items_go_to_the_first_day_once() {
+        let previous = "# TODOS 202608\n\n## 20260828 - Viernes\n\n- [ ] 1. [[Ejercicio]]\n\
+                        - [ ] 2. Llamar al banco\n- [ ] 3. Revisar correo\n\n## 20260830 - Domingo\n\n\
+                        - [ ] 2. Llamar al banco\n- [x] 3. Revisar correo\n\
+                        - [ ] 4. Pagar la luz (pendiente del 20260715)\n- [-] 5. Comprar boletos\n";
+        let todos = Todos::new(2026, 9, PathBuf::from("."))
+            .with_locale(Locale::En)
+            .with_carried(MdParser::parse(previous));
+        let content = render_todos(&todos).unwrap();
+
+        assert!(content.contains(
+            "## 20260901 - Tuesday\n\n- [ ] 1. [[Ejercicio]]\n\
+             - [ ] 2. Trabajar en CBI (09:00-17:00)\n\
+             - [ ] 2. Llamar al banco (carried over from 20260830)\n"
+        ));
+        assert!(content.contains("- [ ] 4. Pagar la luz (pendiente del 20260715)\n- [ ] 5."));
+        assert_eq!(content.matches("Llamar al banco").count(), 1);
+        assert!(!content.contains("Revisar correo"));
+        assert!(!content.contains("Comprar boletos"));
+        assert_eq!(
+            content.matches("Ejercicio").count(),
+            render_todos(&Todos::new(2026, 9, PathBuf::from(".")))
+                .unwrap()
+                .matches("Ejercicio")
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_previous_md_path() {
+        let dir = PathBuf::from("/tmp/todos");
+        let previous = |todos: Todos| todos.previous_md_path();
+        assert_eq!(
+            previous(Todos::new(2027, 1, dir.clone())),
+            dir.join("TODOS - 202612.md")
+        );
+        assert_eq!(
+            previous(Todos::new_week(2027, 1, dir.clone())),
+            dir.join("TODOS - 2026-W53.md")
+        );
+        assert_eq!(
+            previous(Todos::new_week(2026, 33, dir.clone())),
+            dir.join("TODOS - 2026-W32.md")
+        );
+    }
+
+    #[test]
+    fn test_from_stem() {
+        let dir = PathBuf::from("/tmp/todos");
+        let period = |stem: &str| Todos::from_stem(stem, dir.clone()).map(|todos| todos.period());
+        assert_eq!(period("TODOS - 202608").as_deref(), Some("202608"));
+        assert_eq!(period("TODOS - 2026-W33").as_deref(), Some("2026-W33"));
+        assert_eq!(period("TODOS - 202613"), None);
+        assert_eq!(period("TODOS - 2027-W53"), None);
+        assert_eq!(period("Agosto"), None);
+    }
+
+    #[test]
+    fn test_create_ics_file_keeps_markdown_edits() {
+        let dir = std::env::temp_dir().join(format!("todos-lib-ics-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let md_path = dir.join("TODOS - 202608.md");
+        let ics_path = dir.join("TODOS - 202608.ics");
+        let md = "# TODOS 202608\n\n## 20260801 - Sábado\n\n- [ ] 1. Tarea editada\n";
+        fs::write(&md_path, md).unwrap();
+
+        create_ics_file(
+            &md_path,
+            &ics_path,
+            "TODOS - 202608",
+            &[],
+            &IcsOptions::default(),
+            OverwriteMode::Force,
+            Locale::Es,
+        )
+        .unwrap();
+
+        let ics = fs::read_to_string(&ics_path).unwrap();
+        assert!(ics.contains("SUMMARY:[P1] Tarea editada"));
+        assert_eq!(fs::read_to_string(&md_path).unwrap(), md);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,326 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeSet;
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::locale::{Locale, parse_weekday};
+use crate::parser::{MdParser, TodoStatus};
+
+/// Most tasks a day can plan with the Ivy Lee method.
+pub const MAX_TASKS: usize = 6;
+
+/// A problem found by `lint`, with the line replacements fixing it when the fix is
+/// unambiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub diagnostic: Diagnostic,
+    /// `(line, new text)` pairs, empty when the problem has to be fixed by hand.
+    pub fixes: Vec<(usize, String)>,
+}
+
+impl Finding {
+    fn new(diagnostic: Diagnostic) -> Self {
+        Self {
+            diagnostic,
+            fixes: Vec::new(),
+        }
+    }
+
+    pub fn fixable(&self) -> bool {
+        !self.fixes.is_empty()
+    }
+}
+
+/// A task line of a day, with the priority written in it.
+struct Task<'a> {
+    line: usize,
+    text: &'a str,
+    /// Byte offset of the priority in the line.
+    offset: usize,
+    /// Length of the priority as written.
+    digits: usize,
+    priority: Option<u8>,
+}
+
+/// Checks a TODOS file against the Ivy Lee method: at most six tasks a day, ranked
+/// from 1 without repeating or skipping priorities, headings whose weekday matches
+/// their date, and one heading for each of `days`. Without `days`, every day between
+/// the first and last heading is expected. Problems found by the parser are included.
+pub fn lint(content: &str, days: Option<&[NaiveDate]>, locale: Locale) -> Vec<Finding> {
+    let (_, diagnostics) = MdParser::parse_with_diagnostics(content);
+    let mut findings: Vec<Finding> = diagnostics.into_iter().map(Finding::new).collect();
+
+    let (header, blocks) = MdParser::split_days(content);
+    let mut line = header.lines().count() + 1;
+    let mut seen: Vec<(NaiveDate, usize)> = Vec::new();
+    for block in &blocks {
+        let heading = block.content.lines().next().unwrap_or_default();
+        if let Some(&(_, first)) = seen.iter().find(|(date, _)| *date == block.date) {
+            findings.push(Finding::new(Diagnostic::new(
+                Severity::Warning,
+                line,
+                heading,
+                3,
+                format!(
+                    "{} appears twice, first at line {}",
+                    block.date.format("%Y%m%d"),
+                    first
+                ),
+            )));
+        } else {
+            seen.push((block.date, line));
+        }
+        // The parser warns about a heading whose weekday is wrong; this adds the fix.
+        if let Some(fixed) = fix_weekday(block.date, heading, locale)
+            && let Some(finding) = findings
+                .iter_mut()
+                .find(|finding| finding.diagnostic.line == line)
+        {
+            finding.fixes.push((line, fixed));
+        }
+
+        let tasks: Vec<Task> = block
+            .content
+            .lines()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(index, text)| {
+                let (_, rest) = TodoStatus::parse_checkbox(text)?;
+                let (priority, _) = rest.split_once(". ")?;
+                Some(Task {
+                    line: line + index,
+                    text,
+                    offset: text.len() - rest.len(),
+                    digits: priority.len(),
+                    priority: priority.parse().ok().filter(|p| (1..=6).contains(p)),
+                })
+            })
+            .collect();
+        findings.extend(check_priorities(block.date, &tasks));
+
+        line += block.content.lines().count();
+    }
+
+    let expected: Vec<NaiveDate> = match (days, seen.iter().map(|(date, _)| *date).min()) {
+        (Some(days), _) => days.to_vec(),
+        (None, Some(first)) => {
+            let last = seen.iter().map(|(date, _)| *date).max().unwrap_or(first);
+            first.iter_days().take_while(|day| *day <= last).collect()
+        }
+        (None, None) => Vec::new(),
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    for day in expected {
+        if seen.iter().any(|(date, _)| *date == day) {
+            continue;
+        }
+        // Reported at the heading of the next day, or at the end of the file.
+        let at = seen
+            .iter()
+            .filter(|(date, _)| *date > day)
+            .min()
+            .map_or(lines.len().max(1), |(_, line)| *line);
+        findings.push(Finding::new(Diagnostic::new(
+            Severity::Warning,
+            at,
+            lines.get(at - 1).copied().unwrap_or_default(),
+            0,
+            format!("missing day {}", day.format("%Y%m%d")),
+        )));
+    }
+
+    findings.sort_by_key(|finding| (finding.diagnostic.line, finding.diagnostic.column));
+    findings
+}
+
+/// The heading with the weekday of its date in `locale`, when it has a weekday text
+/// that is not the right one.
+fn fix_weekday(date: NaiveDate, heading: &str, locale: Locale) -> Option<String> {
+    let (_, text) = heading.split_once(" - ")?;
+    (parse_weekday(text.trim_end()) != Some(date.weekday())).then(|| {
+        format!(
+            "## {} - {}",
+            date.format("%Y%m%d"),
+            locale.weekday_name(date.weekday())
+        )
+    })
+}
+
+/// Flags days with too many tasks and repeated or skipped priorities. Skipped
+/// priorities are fixed by renumbering the day when no priority repeats.
+fn check_priorities(date: NaiveDate, tasks: &[Task]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let day = date.format("%Y%m%d");
+    if let Some(extra) = tasks.get(MAX_TASKS) {
+        findings.push(Finding::new(Diagnostic::new(
+            Severity::Warning,
+            extra.line,
+            extra.text,
+            0,
+            format!(
+                "{} has {} tasks, the Ivy Lee method plans at most {}",
+                day,
+                tasks.len(),
+                MAX_TASKS
+            ),
+        )));
+    }
+
+    let mut used = BTreeSet::new();
+    for task in tasks {
+        if let Some(priority) = task.priority
+            && !used.insert(priority)
+        {
+            findings.push(Finding::new(Diagnostic::new(
+                Severity::Warning,
+                task.line,
+                task.text,
+                task.offset,
+                format!("priority {} is used twice in {}", priority, day),
+            )));
+        }
+    }
+
+    let skipped: Vec<String> = (1..=used.last().copied().unwrap_or(0))
+        .filter(|priority| !used.contains(priority))
+        .map(|priority| priority.to_string())
+        .collect();
+    if skipped.is_empty() {
+        return findings;
+    }
+    // First task whose priority comes after a skipped one.
+    let rank = |priority: u8| used.iter().position(|p| *p == priority).unwrap_or(0) + 1;
+    let Some(first) = tasks
+        .iter()
+        .find(|task| task.priority.is_some_and(|p| usize::from(p) != rank(p)))
+    else {
+        return findings;
+    };
+    let unambiguous = findings.is_empty() && tasks.iter().all(|task| task.priority.is_some());
+    let fixes = if unambiguous {
+        tasks
+            .iter()
+            .filter_map(|task| {
+                let priority = task.priority?;
+                (usize::from(priority) != rank(priority)).then(|| {
+                    let text = task.text;
+                    let renumbered = format!(
+                        "{}{}{}",
+                        &text[..task.offset],
+                        rank(priority),
+                        &text[task.offset + task.digits..]
+                    );
+                    (task.line, renumbered)
+                })
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    findings.push(Finding {
+        diagnostic: Diagnostic::new(
+            Severity::Warning,
+            first.line,
+            first.text,
+            first.offset,
+            match skipped.as_slice() {
+                [priority] => format!("priority {} is skipped in {}", priority, day),
+                _ => format!("priorities {} are skipped in {}", skipped.join(", "), day),
+            },
+        ),
+        fixes,
+    });
+    findings
+}
+
+/// Applies the fixes of `findings` to `content`, keeping its line endings.
+pub fn fix(content: &str, findings: &[Finding]) -> String {
+    let fixes: Vec<&(usize, String)> = findings.iter().flat_map(|finding| &finding.fixes).collect();
+    content
+        .split_inclusive('\n')
+        .enumerate()
+        .map(
+            |(index, text)| match fixes.iter().find(|(line, _)| *line == index + 1) {
+                Some((_, fixed)) => {
+                    let ending = &text[text.trim_end_matches(['\r', '\n']).len()..];
+                    format!("{}{}", fixed, ending)
+                }
+                None => text.to_string(),
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn messages(findings: &[Finding]) -> Vec<(usize, &str)> {
+        findings
+            .iter()
+            .map(|finding| (finding.diagnostic.line, finding.diagnostic.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_file() {
+        let content = "# TODOS 202608\n\n## 20260801 - Sábado\n\n- [ ] 1. Ejercicio\n- [x] 2. Leer\n\n## 20260802 - Domingo\n\n- [ ] 1. Descansar\n";
+        let days = [date(2026, 8, 1), date(2026, 8, 2)];
+        assert!(lint(content, Some(&days), Locale::Es).is_empty());
+        assert!(lint(content, None, Locale::Es).is_empty());
+    }
+
+    #[test]
+    fn test_ivy_lee_rules() {
+        let content = "# TODOS 202608\n\
+                       ## 20260801 - Lunes\n\
+                       - [ ] 1. A\n- [ ] 2. B\n- [ ] 2. C\n- [ ] 3. D\n- [ ] 4. E\n- [ ] 5. F\n- [ ] 6. G\n\
+                       ## 20260803 - Monday\n\
+                       - [ ] 1. A\n- [ ] 3. B\n\
+                       ## 20260801 - Sábado\n\
+                       - [ ] 9. A\n";
+        let days: Vec<NaiveDate> = (1..=4).map(|day| date(2026, 8, day)).collect();
+        let findings = lint(content, Some(&days), Locale::Es);
+        assert_eq!(
+            messages(&findings),
+            vec![
+                (2, "'Lunes' is not the weekday of 20260801, using Saturday"),
+                (5, "priority 2 is used twice in 20260801"),
+                (
+                    9,
+                    "20260801 has 7 tasks, the Ivy Lee method plans at most 6"
+                ),
+                (10, "missing day 20260802"),
+                (12, "priority 2 is skipped in 20260803"),
+                (13, "20260801 appears twice, first at line 2"),
+                (14, "missing day 20260804"),
+                (14, "priority 9 outside 1 to 6, using 6"),
+            ]
+        );
+        let fixable: Vec<usize> = findings
+            .iter()
+            .filter(|finding| finding.fixable())
+            .map(|finding| finding.diagnostic.line)
+            .collect();
+        assert_eq!(fixable, vec![2, 12]);
+    }
+
+    #[test]
+    fn test_fix_renumbers_and_renames() {
+        let content = "## 20260801 - Lunes\r\n- [ ] 4. A\r\n- [x] 2. B\r\n- [ ] 7. C\r\n";
+        let findings = lint(content, None, Locale::En);
+        assert_eq!(
+            fix(content, &findings),
+            "## 20260801 - Saturday\r\n- [ ] 4. A\r\n- [x] 2. B\r\n- [ ] 7. C\r\n",
+            "a day with a priority outside 1 to 6 is not renumbered"
+        );
+
+        let content = "## 20260801 - Sábado\n- [ ] 4. A\n- [x] 2. B\n";
+        let fixed = fix(content, &lint(content, None, Locale::Es));
+        assert_eq!(fixed, "## 20260801 - Sábado\n- [ ] 2. A\n- [x] 1. B\n");
+        assert!(lint(&fixed, None, Locale::Es).is_empty());
+    }
+}
@@ -0,0 +1,379 @@
+use chrono::Weekday;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Language of weekday and month names and of console messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Es,
+    En,
+}
+
+pub const LOCALES: [Locale; 2] = [Locale::Es, Locale::En];
+
+const ES_WEEKDAYS: [&str; 7] = [
+    "Lunes",
+    "Martes",
+    "Miércoles",
+    "Jueves",
+    "Viernes",
+    "Sábado",
+    "Domingo",
+];
+
+const EN_WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const ES_MONTHS: [&str; 12] = [
+    "Enero",
+    "Febrero",
+    "Marzo",
+    "Abril",
+    "Mayo",
+    "Junio",
+    "Julio",
+    "Agosto",
+    "Septiembre",
+    "Octubre",
+    "Noviembre",
+    "Diciembre",
+];
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Spanish and English abbreviations, indexed from Monday.
+const ABBREVIATIONS: [&[&str]; 7] = [
+    &["lun", "lu", "mon"],
+    &["mar", "ma", "tue", "tues"],
+    &["mie", "mi", "x", "wed"],
+    &["jue", "ju", "thu", "thur", "thurs"],
+    &["vie", "vi", "fri"],
+    &["sab", "sa", "sat"],
+    &["dom", "do", "sun"],
+];
+
+fn fold(text: &str) -> String {
+    text.trim()
+        .trim_end_matches('.')
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' => 'a',
+            'é' | 'è' => 'e',
+            'í' | 'ì' => 'i',
+            'ó' | 'ò' => 'o',
+            'ú' | 'ù' | 'ü' => 'u',
+            c => c,
+        })
+        .collect()
+}
+
+/// Parses a weekday written in any supported locale, ignoring case and accents.
+///
+/// Accepts full names (`Miércoles`, `miercoles`, `Wednesday`), abbreviations
+/// (`Mié`, `Wed`) and ISO 8601 numbers (`1` for Monday to `7` for Sunday).
+pub fn parse_weekday(text: &str) -> Option<Weekday> {
+    let folded = fold(text);
+    if folded.is_empty() {
+        return None;
+    }
+    if let Ok(number) = folded.parse::<u8>() {
+        return (1..=7)
+            .contains(&number)
+            .then(|| Weekday::try_from(number - 1).unwrap());
+    }
+    (0..7)
+        .map(|n| Weekday::try_from(n).unwrap())
+        .find(|weekday| {
+            let index = weekday.num_days_from_monday() as usize;
+            LOCALES
+                .iter()
+                .any(|locale| fold(locale.weekday_name(*weekday)) == folded)
+                || ABBREVIATIONS[index].contains(&folded.as_str())
+        })
+}
+
+/// Kind of generated file, named in console messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Todos,
+    Ics,
+    Template,
+    DailyNote,
+}
+
+impl Locale {
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::Es => "es",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+        let names = match self {
+            Locale::Es => &ES_WEEKDAYS,
+            Locale::En => &EN_WEEKDAYS,
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Name of `month` (1-12).
+    pub fn month_name(self, month: u32) -> &'static str {
+        let names = match self {
+            Locale::Es => &ES_MONTHS,
+            Locale::En => &EN_MONTHS,
+        };
+        names[(month as usize - 1) % 12]
+    }
+
+    fn file_kind(self, kind: FileKind) -> &'static str {
+        match (self, kind) {
+            (_, FileKind::Todos) => "TODOS",
+            (_, FileKind::Ics) => "ICS",
+            (Locale::Es, FileKind::Template) => "de plantilla",
+            (Locale::En, FileKind::Template) => "Template",
+            (Locale::Es, FileKind::DailyNote) => "de nota diaria",
+            (Locale::En, FileKind::DailyNote) => "Daily note",
+        }
+    }
+
+    pub fn file_created(self, kind: FileKind, path: &Path) -> String {
+        match self {
+            Locale::Es => format!(
+                "Archivo {} creado: {}",
+                self.file_kind(kind),
+                path.display()
+            ),
+            Locale::En => format!("{} file created: {}", self.file_kind(kind), path.display()),
+        }
+    }
+
+    pub fn file_replaced(self, kind: FileKind, path: &Path) -> String {
+        match self {
+            Locale::Es => format!(
+                "Archivo {} reemplazado: {}",
+                self.file_kind(kind),
+                path.display()
+            ),
+            Locale::En => format!("{} file replaced: {}", self.file_kind(kind), path.display()),
+        }
+    }
+
+    pub fn file_backed_up(self, kind: FileKind, path: &Path, backup: &Path) -> String {
+        match self {
+            Locale::Es => format!(
+                "Archivo {} reemplazado: {} (respaldo: {})",
+                self.file_kind(kind),
+                path.display(),
+                backup.display()
+            ),
+            Locale::En => format!(
+                "{} file replaced: {} (backup: {})",
+                self.file_kind(kind),
+                path.display(),
+                backup.display()
+            ),
+        }
+    }
+
+    pub fn file_kept(self, kind: FileKind, path: &Path) -> String {
+        match self {
+            Locale::Es => format!(
+                "Archivo {} conservado: {}",
+                self.file_kind(kind),
+                path.display()
+            ),
+            Locale::En => format!("{} file kept: {}", self.file_kind(kind), path.display()),
+        }
+    }
+
+    pub fn existing_files_kept(self) -> &'static str {
+        match self {
+            Locale::Es => "Se conservaron los archivos existentes.",
+            Locale::En => "The existing files were kept.",
+        }
+    }
+
+    pub fn merge_kept(self, past: usize, checked: usize, edited: usize) -> String {
+        match self {
+            Locale::Es => format!(
+                "Días conservados: {} pasados, {} con tareas marcadas, {} editados",
+                past, checked, edited
+            ),
+            Locale::En => format!(
+                "Days kept: {} past, {} with checked items, {} edited",
+                past, checked, edited
+            ),
+        }
+    }
+
+    pub fn merge_updated(self, updated: usize, unchanged: usize) -> String {
+        match self {
+            Locale::Es => format!("Días actualizados: {} ({} sin cambios)", updated, unchanged),
+            Locale::En => format!("Days updated: {} ({} unchanged)", updated, unchanged),
+        }
+    }
+
+    pub fn merge_conflict(self, yyyymmdd: &str) -> String {
+        match self {
+            Locale::Es => format!(
+                "Conflicto {}: se conservaron las ediciones manuales, las plantillas también cambiaron",
+                yyyymmdd
+            ),
+            Locale::En => format!(
+                "Conflict {}: manual edits were kept, the templates changed too",
+                yyyymmdd
+            ),
+        }
+    }
+
+    /// Words before the original date of a carried over task, e.g. `pendiente del`.
+    pub fn carried_from(self) -> &'static str {
+        match self {
+            Locale::Es => "pendiente del",
+            Locale::En => "carried over from",
+        }
+    }
+
+    pub fn stats_summary(self, name: &str, days: usize, tasks: usize) -> String {
+        match self {
+            Locale::Es => format!("{}: {} días, {} tareas", name, days, tasks),
+            Locale::En => format!("{}: {} days, {} tasks", name, days, tasks),
+        }
+    }
+
+    pub fn lint_summary(self, name: &str, problems: usize) -> String {
+        match self {
+            Locale::Es => format!("{}: {} problemas", name, problems),
+            Locale::En => format!("{}: {} problems", name, problems),
+        }
+    }
+
+    pub fn lint_fixed(self, fixed: usize) -> String {
+        match self {
+            Locale::Es => format!("Problemas corregidos: {}", fixed),
+            Locale::En => format!("Problems fixed: {}", fixed),
+        }
+    }
+
+    pub fn lint_fixable(self, fixable: usize) -> String {
+        match self {
+            Locale::Es => format!("{} se pueden corregir con --fix", fixable),
+            Locale::En => format!("{} can be fixed with --fix", fixable),
+        }
+    }
+
+    pub fn stats_status(self, done: usize, cancelled: usize, deferred: usize) -> String {
+        match self {
+            Locale::Es => format!(
+                "Hechas: {}, canceladas: {}, pospuestas: {}",
+                done, cancelled, deferred
+            ),
+            Locale::En => format!(
+                "Done: {}, cancelled: {}, deferred: {}",
+                done, cancelled, deferred
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_lowercase();
+        let language = code.split(['-', '_', '.']).next().unwrap_or_default();
+        match language {
+            "es" => Ok(Locale::Es),
+            "en" => Ok(Locale::En),
+            _ => Err(format!("unsupported locale '{}', expected es or en", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weekday_names() {
+        assert_eq!(Locale::Es.weekday_name(Weekday::Wed), "Miércoles");
+        assert_eq!(Locale::En.weekday_name(Weekday::Sun), "Sunday");
+        assert_eq!(Locale::Es.month_name(8), "Agosto");
+        assert_eq!(Locale::En.month_name(12), "December");
+    }
+
+    #[test]
+    fn test_parse_weekday_accepts_every_locale() {
+        assert_eq!(parse_weekday("Lunes"), Some(Weekday::Mon));
+        assert_eq!(parse_weekday("Monday"), Some(Weekday::Mon));
+        assert_eq!(parse_weekday("Sábado"), Some(Weekday::Sat));
+        assert_eq!(parse_weekday("Someday"), None);
+        assert_eq!(parse_weekday(""), None);
+    }
+
+    #[test]
+    fn test_parse_weekday_ignores_case_and_accents() {
+        for text in [
+            "Miércoles",
+            "Miercoles",
+            "miércoles",
+            "MIERCOLES",
+            " miercoles ",
+        ] {
+            assert_eq!(parse_weekday(text), Some(Weekday::Wed), "{}", text);
+        }
+        assert_eq!(parse_weekday("sabado"), Some(Weekday::Sat));
+    }
+
+    #[test]
+    fn test_parse_weekday_abbreviations_and_numbers() {
+        assert_eq!(parse_weekday("Wed"), Some(Weekday::Wed));
+        assert_eq!(parse_weekday("Mié."), Some(Weekday::Wed));
+        assert_eq!(parse_weekday("jue"), Some(Weekday::Thu));
+        assert_eq!(parse_weekday("Thurs"), Some(Weekday::Thu));
+        assert_eq!(parse_weekday("1"), Some(Weekday::Mon));
+        assert_eq!(parse_weekday("3"), Some(Weekday::Wed));
+        assert_eq!(parse_weekday("7"), Some(Weekday::Sun));
+        assert_eq!(parse_weekday("0"), None);
+        assert_eq!(parse_weekday("8"), None);
+    }
+
+    #[test]
+    fn test_parse_locale_codes() {
+        assert_eq!("es".parse::<Locale>(), Ok(Locale::Es));
+        assert_eq!("EN".parse::<Locale>(), Ok(Locale::En));
+        assert_eq!("en_US.UTF-8".parse::<Locale>(), Ok(Locale::En));
+        assert_eq!("es-MX".parse::<Locale>(), Ok(Locale::Es));
+        assert!("fr".parse::<Locale>().is_err());
+    }
+}
@@ -1,99 +1,1103 @@
-use clap::Parser;
+use chrono::{Datelike, NaiveTime};
+use clap::{Parser, Subcommand};
 use std::fs;
-use std::path::PathBuf;
-use todos_cli::calendar::generate_ics;
-use todos_cli::parser::{CsvParser, MdParser};
-use todos_cli::{Todos, create_todos_file};
+use std::path::{Path, PathBuf};
+use todos_cli::calendar::{IcsOptions, ProfileRules};
+use todos_cli::catalogue::TaskCatalogue;
+use todos_cli::config::{self, Overrides, Setting, Settings, Source, TEMPLATES_DIR};
+use todos_cli::diagnostics::{self, Diagnostic};
+use todos_cli::holidays::Holidays;
+use todos_cli::lint;
+use todos_cli::locale::{FileKind, Locale};
+use todos_cli::merge::{MergeReport, Resolution};
+use todos_cli::output::{
+    Layout, OverwriteMode, check_distinct, check_existing, parse_daily_pattern, report,
+};
+use todos_cli::parser::{CsvParser, DueTimeRule, MdParser, TodoItem, TodoStatus};
+use todos_cli::profiles::Profile;
+use todos_cli::recurrence::RecurringTasks;
+use todos_cli::templates;
+use todos_cli::{
+    Todos, create_combined_ics_file, create_ics_file, create_todos_file, merge_todos_file,
+    months_between, parse_year_month, range_stem, read_todo_items,
+    read_todo_items_with_diagnostics,
+};
 
 /// Command line arguments for todos-cli
 #[derive(Parser)]
-#[command(author = "Federico Aguirre", version = env!("CARGO_PKG_VERSION"), about = "This CLI creates a TODO file for a given month.", long_about = None)]
-struct Args {
+#[command(author = "Federico Aguirre", version = env!("CARGO_PKG_VERSION"), about = "This CLI creates and manages monthly TODO files.", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    /// Directory with templates overriding the built-in ones
+    #[arg(long, global = true)]
+    templates_dir: Option<PathBuf>,
+
+    /// Due-time rules CSV file
+    #[arg(long, global = true)]
+    rules: Option<PathBuf>,
+
+    /// Calendar name of the ICS files [default: the file name]
+    #[arg(long, global = true)]
+    calendar_name: Option<String>,
+
+    /// Default event duration in minutes [default: 60]
+    #[arg(long, global = true)]
+    event_duration: Option<u32>,
+
+    /// Start time (HH:MM) of events without a due-time rule [default: 09:00]
+    #[arg(long, global = true, value_parser = config::parse_time)]
+    start_time: Option<NaiveTime>,
+
+    /// Language of weekday names and messages: es or en [default: es]
+    #[arg(long, global = true)]
+    locale: Option<Locale>,
+
+    /// Markdown layout: single (one TODOS file) or daily (daily notes) [default: single]
+    #[arg(long, global = true)]
+    layout: Option<Layout>,
+
+    /// Folder of the daily notes, relative to the output path [default: .]
+    #[arg(long, global = true)]
+    daily_folder: Option<PathBuf>,
+
+    /// File name pattern (chrono strftime) of the daily notes [default: %Y-%m-%d]
+    #[arg(long, global = true, value_parser = parse_daily_pattern)]
+    daily_pattern: Option<String>,
+
+    /// Start TODOS files with YAML frontmatter for Dataview queries [default: false]
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true")]
+    frontmatter: Option<bool>,
+
+    /// Comma-separated tags of the YAML frontmatter [default: todos]
+    #[arg(long, global = true, value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+
+    /// Holidays list (CSV or ICS) of days rendered from a holiday template
+    #[arg(long, global = true)]
+    holidays: Option<PathBuf>,
+
+    /// Render every day from FROM to TO (YYYY-MM-DD) with TEMPLATE and no due-time
+    /// rules, as TEMPLATE:FROM:TO; repeatable
+    #[arg(long = "profile", global = true, value_parser = Profile::parse)]
+    profiles: Vec<Profile>,
+
+    /// Recurring tasks CSV (rule,priority,description) added to the matching days
+    #[arg(long, global = true)]
+    recurring: Option<PathBuf>,
+
+    /// Task catalogue (TOML) rendering the days instead of the weekday templates
+    #[arg(long, global = true)]
+    tasks: Option<PathBuf>,
+
+    /// Fail when the markdown or rules files have malformed lines [default: false]
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true")]
+    strict: Option<bool>,
+}
+
+impl Cli {
+    fn overrides(&self) -> Overrides {
+        let path = match &self.command {
+            Commands::New(args) => args.path.as_ref(),
+            Commands::Ics(args) | Commands::List(args) | Commands::Stats(args) => {
+                args.path.as_ref()
+            }
+            Commands::Lint(args) => args.source.path.as_ref(),
+            Commands::Templates { .. } | Commands::Config { .. } => None,
+        };
+        Overrides {
+            path: path.map(PathBuf::from),
+            templates_dir: self.templates_dir.clone(),
+            rules: self.rules.clone(),
+            calendar_name: self.calendar_name.clone(),
+            event_duration: self.event_duration,
+            start_time: self.start_time,
+            locale: self.locale,
+            layout: self.layout,
+            daily_folder: self.daily_folder.clone(),
+            daily_pattern: self.daily_pattern.clone(),
+            frontmatter: self.frontmatter,
+            tags: self.tags.clone(),
+            holidays: self.holidays.clone(),
+            profiles: (!self.profiles.is_empty()).then(|| self.profiles.clone()),
+            recurring: self.recurring.clone(),
+            tasks: self.tasks.clone(),
+            strict: self.strict,
+        }
+    }
+
+    fn settings(&self) -> Settings {
+        Settings::from_env(&self.overrides()).unwrap_or_else(|e| {
+            eprintln!("Error reading configuration: {}", e);
+            std::process::exit(1);
+        })
+    }
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Create the TODOS markdown and ICS files for a given month
+    New(NewArgs),
+    /// Regenerate the ICS file from an existing (possibly edited) TODOS file
+    Ics(SourceArgs),
+    /// List the todo items of an existing TODOS file
+    List(SourceArgs),
+    /// Show todo counts per priority of an existing TODOS file
+    Stats(SourceArgs),
+    /// Check an existing TODOS file against the Ivy Lee method rules
+    Lint(LintArgs),
+    /// Manage the markdown templates and due-time rules
+    Templates {
+        #[command(subcommand)]
+        command: TemplatesCommand,
+    },
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print every resolved setting and where it came from
+    Show,
+}
+
+#[derive(Subcommand)]
+enum TemplatesCommand {
+    /// Write the built-in templates and due-time rules to a directory for editing
+    Init(TemplatesInitArgs),
+}
+
+/// Arguments of the templates init subcommand
+#[derive(clap::Args)]
+struct TemplatesInitArgs {
+    /// Directory to write the templates to [default: --templates-dir, TODOS_TEMPLATES_DIR or templates]
+    #[arg(short = 'd', long)]
+    dir: Option<PathBuf>,
+
+    /// Replace existing template files
+    #[arg(long)]
+    force: bool,
+}
+
+/// Arguments of the new subcommand
+#[derive(clap::Args)]
+#[command(group(clap::ArgGroup::new("range").args(["from", "year_only"])))]
+struct NewArgs {
     /// Year for the TODOS file
-    #[arg(short = 'y', long, required = true)]
-    year: i32,
+    #[arg(short = 'y', long, required_unless_present = "from")]
+    year: Option<i32>,
 
     /// Month for the TODOS file (1-12)
-    #[arg(short = 'm', long, required = true, value_parser = clap::value_parser!(u32).range(1..=12))]
-    month: u32,
+    #[arg(short = 'm', long, required_unless_present_any = ["from", "year_only", "week"], value_parser = clap::value_parser!(u32).range(1..=12))]
+    month: Option<u32>,
+
+    /// ISO week (1-53) of --year to plan in a weekly TODOS file instead of a month
+    #[arg(short = 'w', long, requires = "year", conflicts_with_all = ["month", "year_only", "from"], value_parser = clap::value_parser!(u32).range(1..=53))]
+    week: Option<u32>,
+
+    /// Generate every month of --year
+    #[arg(long, requires = "year", conflicts_with = "month")]
+    year_only: bool,
+
+    /// First month (YYYY-MM) of a range of months to generate
+    #[arg(long, requires = "to", conflicts_with_all = ["year", "month", "year_only"], value_parser = parse_year_month)]
+    from: Option<(i32, u32)>,
+
+    /// Last month (YYYY-MM) of the range started by --from
+    #[arg(long, requires = "from", value_parser = parse_year_month)]
+    to: Option<(i32, u32)>,
+
+    /// Also write a single ICS file with the events of every generated month
+    #[arg(long, requires = "range")]
+    combined_ics: bool,
 
     /// Output file path for the TODOS file
     #[arg(short = 'p', long)]
     path: Option<String>,
+
+    /// Replace existing TODOS and ICS files for the month
+    #[arg(long, conflicts_with_all = ["backup", "merge"])]
+    force: bool,
+
+    /// Keep timestamped copies of existing files before replacing them
+    #[arg(long)]
+    backup: bool,
+
+    /// Re-render future days of an existing TODOS file from the current templates,
+    /// keeping past days, checked items and manual edits
+    #[arg(long)]
+    merge: bool,
+
+    /// Plan the open items of the previous month or week again on the first day
+    #[arg(long)]
+    carry_over: bool,
+}
+
+impl NewArgs {
+    fn overwrite(&self) -> OverwriteMode {
+        if self.force || (self.merge && !self.backup) {
+            OverwriteMode::Force
+        } else if self.backup {
+            OverwriteMode::Backup
+        } else {
+            OverwriteMode::Refuse
+        }
+    }
+
+    /// Months to generate, in order.
+    fn months(&self) -> Vec<(i32, u32)> {
+        match (self.from, self.to, self.year, self.month) {
+            (Some(from), Some(to), _, _) => months_between(from, to),
+            (_, _, Some(year), _) if self.year_only => months_between((year, 1), (year, 12)),
+            (_, _, Some(year), Some(month)) => vec![(year, month)],
+            _ => unreachable!("clap requires --year and --month, --year-only or --from and --to"),
+        }
+    }
+
+    fn todos(&self, settings: &Settings, catalogue: &Option<TaskCatalogue>) -> Vec<Todos> {
+        let path = &settings.path.value;
+        let holidays = load_holidays(settings);
+        let recurring = load_recurring(settings);
+        let periods = match (self.year, self.week) {
+            (Some(year), Some(week)) => vec![Todos::new_week(year, week, path.clone())],
+            _ => self
+                .months()
+                .into_iter()
+                .map(|(year, month)| Todos::new(year, month, path.clone()))
+                .collect(),
+        };
+        let mut periods: Vec<Todos> = periods
+            .into_iter()
+            .map(|todos| {
+                todos
+                    .with_overwrite(self.overwrite())
+                    .with_state_dir(config::state_dir())
+                    .with_templates_dir(settings.templates_dir.value.clone())
+                    .with_locale(settings.locale.value)
+                    .with_daily_notes(settings.daily_notes())
+                    .with_frontmatter(settings.frontmatter())
+                    .with_holidays(holidays.clone())
+                    .with_profiles(settings.profiles.value.clone())
+                    .with_recurring(recurring.clone())
+                    .with_catalogue(catalogue.clone())
+            })
+            .collect();
+        // Later periods of a range would carry over the ones generated in this run.
+        if self.carry_over
+            && let Some(first) = periods.first_mut()
+        {
+            first.carried = load_carried(&first.previous_md_path());
+        }
+        periods
+    }
+}
+
+/// Arguments selecting an existing TODOS file, by path or by year and month or week
+#[derive(clap::Args)]
+struct SourceArgs {
+    /// Existing TODOS markdown file
+    #[arg(short = 'f', long, conflicts_with_all = ["year", "month", "week", "path"])]
+    file: Option<PathBuf>,
+
+    /// Year for the TODOS file
+    #[arg(short = 'y', long, required_unless_present = "file")]
+    year: Option<i32>,
+
+    /// Month for the TODOS file (1-12)
+    #[arg(short = 'm', long, required_unless_present_any = ["file", "week"], value_parser = clap::value_parser!(u32).range(1..=12))]
+    month: Option<u32>,
+
+    /// ISO week (1-53) of a weekly TODOS file
+    #[arg(short = 'w', long, conflicts_with = "month", value_parser = clap::value_parser!(u32).range(1..=53))]
+    week: Option<u32>,
+
+    /// Directory of the TODOS file
+    #[arg(short = 'p', long)]
+    path: Option<String>,
+}
+
+/// Arguments of the lint subcommand
+#[derive(clap::Args)]
+struct LintArgs {
+    #[command(flatten)]
+    source: SourceArgs,
+
+    /// Rewrite the file fixing the problems that have a single possible fix
+    #[arg(long)]
+    fix: bool,
+}
+
+impl SourceArgs {
+    /// Markdown path, ICS path and name of the selected file, looked up in `dir`
+    /// when given by year and month or week.
+    fn files(&self, dir: &Path) -> (PathBuf, PathBuf, String) {
+        let todos = match (&self.file, self.year, self.month, self.week) {
+            (Some(file), _, _, _) => {
+                let name = file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                return (file.clone(), file.with_extension("ics"), name);
+            }
+            (None, Some(year), _, Some(week)) => Todos::new_week(year, week, dir.to_path_buf()),
+            (None, Some(year), Some(month), None) => Todos::new(year, month, dir.to_path_buf()),
+            _ => unreachable!("clap requires --file or --year and --month or --week"),
+        };
+        (todos.md_path(), todos.ics_path(), todos.stem())
+    }
+}
+
+fn ics_options(settings: &Settings, catalogue: Option<TaskCatalogue>) -> IcsOptions {
+    IcsOptions {
+        default_start: settings.start_time.value,
+        default_duration: chrono::Duration::minutes(settings.event_duration.value.into()),
+        profile_rules: settings
+            .profiles
+            .value
+            .0
+            .iter()
+            .map(|profile| ProfileRules {
+                from: profile.from,
+                to: profile.to,
+                rules: load_profile_rules(profile, settings),
+            })
+            .collect(),
+        catalogue,
+    }
+}
+
+fn calendar_name(settings: &Settings, file_name: String) -> String {
+    settings.calendar_name.value.clone().unwrap_or(file_name)
+}
+
+/// Prints the problems found in parsed files, rustc-style. Under `--strict` any of
+/// them makes the command fail.
+fn report_diagnostics(diagnostics: &[Diagnostic], settings: &Settings) {
+    for diagnostic in diagnostics {
+        eprintln!("{}\n", diagnostic);
+    }
+    if settings.strict.value && !diagnostics.is_empty() {
+        eprintln!(
+            "Error: {} problem(s) found, failing because of --strict",
+            diagnostics.len()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Parses a due-time rules CSV, reporting its malformed rows.
+fn parse_rules(csv: &str, path: &Path, settings: &Settings) -> Vec<DueTimeRule> {
+    let (rules, diagnostics) = CsvParser::parse_with_diagnostics(csv);
+    report_diagnostics(&diagnostics::in_file(diagnostics, path), settings);
+    rules
+}
+
+fn load_rules(settings: &Settings) -> Vec<DueTimeRule> {
+    let path = settings
+        .rules
+        .value
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(templates::RULES_FILE));
+    templates::load_rules(settings.rules.value.as_deref())
+        .map(|csv| parse_rules(&csv, &path, settings))
+        .unwrap_or_else(|e| {
+            eprintln!("Warning: could not read CSV: {}, using defaults", e);
+            vec![]
+        })
+}
+
+/// Due-time rules of `profile`, none when it has no rules file.
+fn load_profile_rules(profile: &Profile, settings: &Settings) -> Vec<DueTimeRule> {
+    let Some(path) = &profile.rules else {
+        return vec![];
+    };
+    templates::load_rules(Some(path))
+        .map(|csv| parse_rules(&csv, path, settings))
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: could not read rules {} of profile {}: {}, using no rules",
+                path.display(),
+                profile.name,
+                e
+            );
+            vec![]
+        })
+}
+
+fn load_holidays(settings: &Settings) -> Holidays {
+    let Some(path) = &settings.holidays.value else {
+        return Holidays::default();
+    };
+    match Holidays::load_with_diagnostics(path) {
+        Ok((holidays, diagnostics)) => {
+            report_diagnostics(&diagnostics, settings);
+            holidays
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: could not read holidays {}: {}, using weekday templates",
+                path.display(),
+                e
+            );
+            Holidays::default()
+        }
+    }
+}
+
+fn load_carried(md_path: &Path) -> Vec<TodoItem> {
+    read_todo_items(md_path).unwrap_or_else(|e| {
+        eprintln!(
+            "Warning: could not read {}: {}, nothing to carry over",
+            md_path.display(),
+            e
+        );
+        vec![]
+    })
+}
+
+fn load_catalogue(settings: &Settings) -> Option<TaskCatalogue> {
+    let path = settings.tasks.value.as_ref()?;
+    match TaskCatalogue::load(path) {
+        Ok(catalogue) => Some(catalogue),
+        Err(e) => {
+            eprintln!("Error reading tasks {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn load_recurring(settings: &Settings) -> RecurringTasks {
+    let Some(path) = &settings.recurring.value else {
+        return RecurringTasks::default();
+    };
+    match RecurringTasks::load_with_diagnostics(path) {
+        Ok((tasks, diagnostics)) => {
+            report_diagnostics(&diagnostics, settings);
+            tasks
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: could not read recurring tasks {}: {}, using templates only",
+                path.display(),
+                e
+            );
+            RecurringTasks::default()
+        }
+    }
 }
 
 fn main() {
-    let args = Args::parse();
+    let cli = Cli::parse();
+    let settings = cli.settings();
 
-    let path = args.path.unwrap_or_else(|| {
-        if let Ok(env_path) = std::env::var("TODOS_DEFAULT_PATH") {
-            env_path
-        } else {
-            String::from(".")
+    match cli.command {
+        Commands::New(args) => run_new(&args, &settings),
+        Commands::Ics(args) => run_ics(&args, &settings),
+        Commands::List(args) => run_list(&args, &settings),
+        Commands::Stats(args) => run_stats(&args, &settings),
+        Commands::Lint(args) => run_lint(&args, &settings),
+        Commands::Templates {
+            command: TemplatesCommand::Init(args),
+        } => run_templates_init(&args, &settings),
+        Commands::Config {
+            command: ConfigCommand::Show,
+        } => run_config_show(&settings),
+    }
+}
+
+fn run_config_show(settings: &Settings) {
+    for (key, value, source) in settings.entries() {
+        println!("{} = {}  # {}", key, value, source);
+    }
+}
+
+fn run_templates_init(args: &TemplatesInitArgs, settings: &Settings) {
+    let dir = match (&args.dir, &settings.templates_dir) {
+        (Some(dir), _) => dir.clone(),
+        (
+            None,
+            Setting {
+                value: Some(dir),
+                source: Source::Flag(_) | Source::Env(_),
+            },
+        ) => dir.clone(),
+        _ => PathBuf::from(TEMPLATES_DIR),
+    };
+    let overwrite = if args.force {
+        OverwriteMode::Force
+    } else {
+        OverwriteMode::Refuse
+    };
+    match templates::init(&dir, overwrite) {
+        Ok(written) => {
+            for (path, outcome) in written {
+                match outcome {
+                    Some(outcome) => {
+                        report(settings.locale.value, FileKind::Template, &path, &outcome)
+                    }
+                    None => println!(
+                        "{}",
+                        settings.locale.value.file_kept(FileKind::Template, &path)
+                    ),
+                }
+            }
         }
+        Err(e) => {
+            eprintln!("Error writing templates to {}: {}", dir.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_new(args: &NewArgs, settings: &Settings) {
+    // Loaded once, for both the markdown and the calendars.
+    let catalogue = load_catalogue(settings);
+    let months = args.todos(settings, &catalogue);
+    if let (Some(year), Some(week)) = (args.year, args.week)
+        && months.iter().all(|todos| todos.get_days().is_empty())
+    {
+        eprintln!(
+            "Error creating TODOS file: {} has no ISO week {}",
+            year, week
+        );
+        std::process::exit(1);
+    }
+    let (Some(first), Some(last)) = (months.first(), months.last()) else {
+        eprintln!("Error creating TODOS files: --to is before --from");
+        std::process::exit(1);
+    };
+    let combined = args.combined_ics.then(|| {
+        let stem = range_stem((first.year, first.month), (last.year, last.month));
+        (settings.path.value.join(format!("{}.ics", stem)), stem)
     });
 
-    let output_path: PathBuf = path.into();
-    let todos = Todos::new(args.year, args.month, output_path.clone());
-    if let Err(e) = create_todos_file(&todos) {
+    let merging = |todos: &Todos| args.merge && todos.md_path().exists();
+    let mut targets: Vec<PathBuf> = months
+        .iter()
+        .filter(|todos| !merging(todos))
+        .flat_map(|todos| {
+            let mut paths = todos.md_paths();
+            paths.push(todos.ics_path());
+            paths
+        })
+        .collect();
+    targets.extend(combined.iter().map(|(path, _)| path.clone()));
+    // Every day of the run needs a note of its own, checked before anything is written.
+    let md_paths: Vec<PathBuf> = months.iter().flat_map(Todos::md_paths).collect();
+    if let Err(e) = check_distinct(&md_paths) {
         eprintln!("Error creating TODOS file: {}", e);
         std::process::exit(1);
     }
+    if let Err(e) = check_existing(&targets, args.overwrite()) {
+        eprintln!("Error creating TODOS file: {}", e);
+        eprintln!("{}", settings.locale.value.existing_files_kept());
+        std::process::exit(1);
+    }
 
-    let filename = format!("TODOS - {:04}{:02}.md", args.year, args.month);
-    let md_path = output_path.join(&filename);
-    if let Ok(md_content) = fs::read_to_string(&md_path) {
-        let items = MdParser::parse(&md_content);
-        let rules = fs::read_to_string("templates/todos_due_times.csv")
-            .map(|csv| CsvParser::parse(&csv))
-            .unwrap_or_else(|e| {
-                eprintln!("Warning: could not read CSV: {}, using defaults", e);
-                vec![]
-            });
-        let ics_name = format!("TODOS - {:04}{:02}", args.year, args.month);
-        let ics_content = generate_ics(&ics_name, &items, &rules);
-        let ics_filename = format!("TODOS - {:04}{:02}.ics", args.year, args.month);
-        let ics_path = output_path.join(&ics_filename);
-        if let Err(e) = fs::write(&ics_path, ics_content) {
+    let rules = load_rules(settings);
+    let options = ics_options(settings, catalogue);
+    for todos in &months {
+        if merging(todos) {
+            let today = chrono::Local::now().date_naive();
+            match merge_todos_file(todos, today) {
+                Ok(report) => print_merge_report(&report, todos.locale),
+                Err(e) => {
+                    eprintln!("Error merging TODOS file: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        } else if let Err(e) = create_todos_file(todos) {
+            eprintln!("Error creating TODOS file: {}", e);
+            std::process::exit(1);
+        }
+
+        match create_ics_file(
+            &todos.md_path(),
+            &todos.ics_path(),
+            &calendar_name(settings, todos.stem()),
+            &rules,
+            &options,
+            todos.overwrite,
+            settings.locale.value,
+        ) {
+            Ok(diagnostics) => report_diagnostics(&diagnostics, settings),
+            Err(e) => eprintln!("Error creating ICS file: {}", e),
+        }
+    }
+
+    if let Some((ics_path, stem)) = combined {
+        let md_paths: Vec<PathBuf> = months.iter().map(Todos::md_path).collect();
+        if let Err(e) = create_combined_ics_file(
+            &md_paths,
+            &ics_path,
+            &calendar_name(settings, stem),
+            &rules,
+            &options,
+            args.overwrite(),
+            settings.locale.value,
+        ) {
             eprintln!("Error creating ICS file: {}", e);
-        } else {
-            println!("Archivo ICS creado: {}", ics_path.display());
         }
     }
 }
 
+fn print_merge_report(report: &MergeReport, locale: Locale) {
+    if !report.had_base {
+        eprintln!(
+            "Warning: no base copy of the generated file was found, every changed future day is treated as edited"
+        );
+    }
+    println!(
+        "{}",
+        locale.merge_kept(
+            report.count(Resolution::KeptPast),
+            report.count(Resolution::KeptChecked),
+            report.count(Resolution::KeptEdited)
+        )
+    );
+    println!(
+        "{}",
+        locale.merge_updated(
+            report.count(Resolution::Updated),
+            report.count(Resolution::Unchanged)
+        )
+    );
+    for date in report.conflicts() {
+        println!(
+            "{}",
+            locale.merge_conflict(&date.format("%Y%m%d").to_string())
+        );
+    }
+}
+
+fn run_ics(args: &SourceArgs, settings: &Settings) {
+    let (md_path, ics_path, name) = args.files(&settings.path.value);
+    let rules = load_rules(settings);
+    match create_ics_file(
+        &md_path,
+        &ics_path,
+        &calendar_name(settings, name),
+        &rules,
+        &ics_options(settings, load_catalogue(settings)),
+        OverwriteMode::Force,
+        settings.locale.value,
+    ) {
+        Ok(diagnostics) => report_diagnostics(&diagnostics, settings),
+        Err(e) => {
+            eprintln!("Error creating ICS file from {}: {}", md_path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn read_items(args: &SourceArgs, settings: &Settings) -> Vec<TodoItem> {
+    let (md_path, _, _) = args.files(&settings.path.value);
+    match read_todo_items_with_diagnostics(&md_path) {
+        Ok((items, diagnostics)) => {
+            report_diagnostics(&diagnostics, settings);
+            items
+        }
+        Err(e) => {
+            eprintln!("Error reading {}: {}", md_path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_list(args: &SourceArgs, settings: &Settings) {
+    for item in read_items(args, settings) {
+        let status = match item.status {
+            TodoStatus::Open => String::new(),
+            status => format!("{} ", status.checkbox()),
+        };
+        // Headings may leave the weekday out.
+        let weekday = match item.weekday_name.as_str() {
+            "" => settings.locale.value.weekday_name(item.date.weekday()),
+            name => name,
+        };
+        println!(
+            "{} {} {}[P{}] {}",
+            item.date.format("%Y%m%d"),
+            weekday,
+            status,
+            item.priority,
+            item.description
+        );
+    }
+}
+
+fn run_stats(args: &SourceArgs, settings: &Settings) {
+    let (_, _, name) = args.files(&settings.path.value);
+    let items = read_items(args, settings);
+    let mut days: Vec<_> = items.iter().map(|item| item.date).collect();
+    days.dedup();
+
+    println!(
+        "{}",
+        settings
+            .locale
+            .value
+            .stats_summary(&name, days.len(), items.len())
+    );
+    for priority in 1..=6 {
+        let count = items.iter().filter(|i| i.priority == priority).count();
+        println!("P{}: {}", priority, count);
+    }
+    let count = |status: TodoStatus| items.iter().filter(|i| i.status == status).count();
+    println!(
+        "{}",
+        settings.locale.value.stats_status(
+            count(TodoStatus::Done),
+            count(TodoStatus::Cancelled),
+            count(TodoStatus::Deferred)
+        )
+    );
+}
+
+fn run_lint(args: &LintArgs, settings: &Settings) {
+    let (md_path, _, name) = args.source.files(&settings.path.value);
+    let locale = settings.locale.value;
+    let mut content = fs::read_to_string(&md_path).unwrap_or_else(|e| {
+        eprintln!("Error reading {}: {}", md_path.display(), e);
+        std::process::exit(1);
+    });
+    if !MdParser::daily_note_links(&content).is_empty() {
+        eprintln!(
+            "Error linting {}: daily notes are not supported",
+            md_path.display()
+        );
+        std::process::exit(1);
+    }
+    // Without a period in the file name, the days between its first and last heading.
+    let days = Todos::from_stem(&name, settings.path.value.clone()).map(|todos| todos.get_days());
+
+    let mut findings = lint::lint(&content, days.as_deref(), locale);
+    let fixed = findings.iter().filter(|finding| finding.fixable()).count();
+    if args.fix && fixed > 0 {
+        content = lint::fix(&content, &findings);
+        if let Err(e) = fs::write(&md_path, &content) {
+            eprintln!("Error writing {}: {}", md_path.display(), e);
+            std::process::exit(1);
+        }
+        findings = lint::lint(&content, days.as_deref(), locale);
+    }
+
+    for finding in &findings {
+        eprintln!("{}\n", finding.diagnostic.clone().in_file(&md_path));
+    }
+    if args.fix && fixed > 0 {
+        println!("{}", locale.lint_fixed(fixed));
+    }
+    println!("{}", locale.lint_summary(&name, findings.len()));
+    if !args.fix && fixed > 0 {
+        println!("{}", locale.lint_fixable(fixed));
+    }
+    if !findings.is_empty() {
+        std::process::exit(1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::Parser;
+
+    fn cli_overrides(args: Vec<&str>) -> Overrides {
+        Cli::parse_from(args).overrides()
+    }
+
+    fn parse_month_args(args: Vec<&str>) -> NewArgs {
+        match Cli::parse_from(args).command {
+            Commands::New(args) => args,
+            _ => panic!("expected the new subcommand"),
+        }
+    }
 
     #[test]
     fn parses_valid_args() {
-        let args = vec!["test-bin", "-y", "2025", "-m", "10", "-p", "~"];
-        let parsed = Args::parse_from(args);
-        assert_eq!(parsed.year, 2025);
-        assert_eq!(parsed.month, 10);
+        let args = vec!["test-bin", "new", "-y", "2025", "-m", "10", "-p", "~"];
+        let parsed = parse_month_args(args);
+        assert_eq!(parsed.year, Some(2025));
+        assert_eq!(parsed.month, Some(10));
         assert_eq!(parsed.path.as_deref(), Some("~"));
     }
 
     #[test]
     fn parses_long_args() {
-        let args = vec!["test-bin", "--year", "2024", "--month", "1", "--path", "~"];
-        let parsed = Args::parse_from(args);
-        assert_eq!(parsed.year, 2024);
-        assert_eq!(parsed.month, 1);
+        let args = vec![
+            "test-bin", "new", "--year", "2024", "--month", "1", "--path", "~",
+        ];
+        let parsed = parse_month_args(args);
+        assert_eq!(parsed.year, Some(2024));
+        assert_eq!(parsed.month, Some(1));
         assert_eq!(parsed.path.as_deref(), Some("~"));
     }
 
     #[test]
     fn path_is_optional() {
-        let args = vec!["test-bin", "-y", "2025", "-m", "5"];
-        let parsed = Args::parse_from(args);
-        assert_eq!(parsed.year, 2025);
-        assert_eq!(parsed.month, 5);
+        let args = vec!["test-bin", "new", "-y", "2025", "-m", "5"];
+        let parsed = parse_month_args(args);
+        assert_eq!(parsed.year, Some(2025));
+        assert_eq!(parsed.month, Some(5));
         assert!(parsed.path.is_none());
     }
 
+    #[test]
+    fn refuses_to_overwrite_by_default() {
+        let parsed = parse_month_args(vec!["test-bin", "new", "-y", "2025", "-m", "5"]);
+        assert_eq!(parsed.overwrite(), OverwriteMode::Refuse);
+
+        let parsed = parse_month_args(vec!["test-bin", "new", "-y", "2025", "-m", "5", "--force"]);
+        assert_eq!(parsed.overwrite(), OverwriteMode::Force);
+
+        let parsed = parse_month_args(vec!["test-bin", "new", "-y", "2025", "-m", "5", "--backup"]);
+        assert_eq!(parsed.overwrite(), OverwriteMode::Backup);
+
+        let result = Cli::try_parse_from(vec![
+            "test-bin", "new", "-y", "2025", "-m", "5", "--force", "--backup",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn merge_replaces_files_unless_backup_is_requested() {
+        let parsed = parse_month_args(vec!["test-bin", "new", "-y", "2025", "-m", "5", "--merge"]);
+        assert!(parsed.merge);
+        assert_eq!(parsed.overwrite(), OverwriteMode::Force);
+
+        let parsed = parse_month_args(vec![
+            "test-bin", "new", "-y", "2025", "-m", "5", "--merge", "--backup",
+        ]);
+        assert_eq!(parsed.overwrite(), OverwriteMode::Backup);
+
+        let result = Cli::try_parse_from(vec![
+            "test-bin", "new", "-y", "2025", "-m", "5", "--merge", "--force",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parses_month_ranges() {
+        let parsed = parse_month_args(vec![
+            "test-bin", "new", "--from", "2026-11", "--to", "2027-02",
+        ]);
+        assert_eq!(
+            parsed.months(),
+            vec![(2026, 11), (2026, 12), (2027, 1), (2027, 2)]
+        );
+
+        let parsed = parse_month_args(vec![
+            "test-bin",
+            "new",
+            "-y",
+            "2027",
+            "--year-only",
+            "--combined-ics",
+        ]);
+        assert_eq!(parsed.months().len(), 12);
+        assert!(parsed.combined_ics);
+
+        let parsed = parse_month_args(vec!["test-bin", "new", "-y", "2027", "-m", "3"]);
+        assert_eq!(parsed.months(), vec![(2027, 3)]);
+
+        for args in [
+            vec!["test-bin", "new", "--from", "2027-01"],
+            vec!["test-bin", "new", "--from", "2027-01", "--to", "2027-13"],
+            vec!["test-bin", "new", "-y", "2027", "-m", "1", "--year-only"],
+            vec![
+                "test-bin", "new", "-y", "2027", "--from", "2027-01", "--to", "2027-02",
+            ],
+            vec!["test-bin", "new", "-y", "2027", "-m", "1", "--combined-ics"],
+            vec!["test-bin", "new", "--year-only"],
+        ] {
+            assert!(Cli::try_parse_from(&args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn parses_weeks() {
+        let parsed = parse_month_args(vec!["test-bin", "new", "-y", "2026", "-w", "33"]);
+        assert_eq!(parsed.year, Some(2026));
+        assert_eq!(parsed.week, Some(33));
+        assert_eq!(parsed.month, None);
+
+        let cli = Cli::parse_from(vec!["test-bin", "list", "-y", "2026", "--week", "33"]);
+        let Commands::List(args) = cli.command else {
+            panic!("expected the list subcommand");
+        };
+        let (md_path, _, _) = args.files(Path::new("."));
+        assert_eq!(md_path, PathBuf::from("./TODOS - 2026-W33.md"));
+
+        for args in [
+            vec!["test-bin", "new", "-w", "33"],
+            vec!["test-bin", "new", "-y", "2026", "-m", "8", "-w", "33"],
+            vec!["test-bin", "new", "-y", "2026", "-w", "54"],
+            vec![
+                "test-bin",
+                "new",
+                "-y",
+                "2026",
+                "-w",
+                "33",
+                "--combined-ics",
+            ],
+        ] {
+            assert!(Cli::try_parse_from(&args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn parses_list_and_stats_subcommands() {
+        let cli = Cli::parse_from(vec!["test-bin", "list", "-y", "2025", "-m", "5"]);
+        assert!(matches!(cli.command, Commands::List(_)));
+        let cli = Cli::parse_from(vec!["test-bin", "stats", "-y", "2025", "-m", "5"]);
+        assert!(matches!(cli.command, Commands::Stats(_)));
+    }
+
+    #[test]
+    fn ics_accepts_file_or_year_and_month() {
+        let cli = Cli::parse_from(vec!["test-bin", "ics", "-f", "TODOS - 202608.md"]);
+        let Commands::Ics(args) = cli.command else {
+            panic!("expected the ics subcommand");
+        };
+        let (md_path, ics_path, name) = args.files(Path::new("."));
+        assert_eq!(md_path, PathBuf::from("TODOS - 202608.md"));
+        assert_eq!(ics_path, PathBuf::from("TODOS - 202608.ics"));
+        assert_eq!(name, "TODOS - 202608");
+
+        let cli = Cli::parse_from(vec![
+            "test-bin", "ics", "-y", "2026", "-m", "8", "-p", "out",
+        ]);
+        let Commands::Ics(args) = cli.command else {
+            panic!("expected the ics subcommand");
+        };
+        let overrides = cli_overrides(vec![
+            "test-bin", "ics", "-y", "2026", "-m", "8", "-p", "out",
+        ]);
+        let (md_path, _, _) = args.files(overrides.path.as_deref().unwrap());
+        assert_eq!(md_path, PathBuf::from("out/TODOS - 202608.md"));
+
+        assert!(Cli::try_parse_from(vec!["test-bin", "ics", "-y", "2026"]).is_err());
+        assert!(Cli::try_parse_from(vec!["test-bin", "ics", "-f", "a.md", "-y", "2026"]).is_err());
+    }
+
+    #[test]
+    fn parses_templates_init() {
+        let cli = Cli::parse_from(vec!["test-bin", "templates", "init"]);
+        let Commands::Templates {
+            command: TemplatesCommand::Init(args),
+        } = cli.command
+        else {
+            panic!("expected the templates init subcommand");
+        };
+        assert_eq!(args.dir, None);
+        assert!(!args.force);
+    }
+
+    #[test]
+    fn parses_global_location_flags() {
+        let cli = Cli::parse_from(vec![
+            "test-bin",
+            "new",
+            "-y",
+            "2025",
+            "-m",
+            "5",
+            "--templates-dir",
+            "/dotfiles/todos",
+            "--rules",
+            "/dotfiles/rules.csv",
+        ]);
+        assert_eq!(cli.templates_dir, Some(PathBuf::from("/dotfiles/todos")));
+        assert_eq!(cli.rules, Some(PathBuf::from("/dotfiles/rules.csv")));
+
+        let cli = Cli::parse_from(vec!["test-bin", "--rules", "r.csv", "ics", "-f", "a.md"]);
+        assert_eq!(cli.rules, Some(PathBuf::from("r.csv")));
+    }
+
+    #[test]
+    fn parses_repeated_profiles() {
+        let overrides = cli_overrides(vec![
+            "test-bin",
+            "new",
+            "-y",
+            "2026",
+            "-m",
+            "8",
+            "--profile",
+            "travel.md:2026-08-03:2026-08-09",
+            "--profile",
+            "beach.md:2026-08-20:2026-08-23",
+        ]);
+        let names: Vec<String> = overrides
+            .profiles
+            .unwrap()
+            .into_iter()
+            .map(|profile| profile.name)
+            .collect();
+        assert_eq!(names, vec!["travel", "beach"]);
+        assert!(
+            cli_overrides(vec!["test-bin", "config", "show"])
+                .profiles
+                .is_none()
+        );
+        assert!(
+            Cli::try_parse_from(vec!["test-bin", "config", "show", "--profile", "travel.md"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_event_defaults_and_config_show() {
+        let overrides = cli_overrides(vec![
+            "test-bin",
+            "config",
+            "show",
+            "--calendar-name",
+            "TODOS",
+            "--event-duration",
+            "30",
+            "--start-time",
+            "08:15",
+        ]);
+        assert_eq!(overrides.calendar_name.as_deref(), Some("TODOS"));
+        assert_eq!(overrides.event_duration, Some(30));
+        assert_eq!(overrides.start_time, NaiveTime::from_hms_opt(8, 15, 0));
+        assert_eq!(overrides.path, None);
+
+        let overrides = cli_overrides(vec![
+            "test-bin",
+            "config",
+            "show",
+            "--frontmatter",
+            "--tags",
+            "todos,ivy-lee",
+        ]);
+        assert_eq!(overrides.frontmatter, Some(true));
+        assert_eq!(
+            overrides.tags,
+            Some(vec!["todos".to_string(), "ivy-lee".to_string()])
+        );
+        let overrides = cli_overrides(vec!["test-bin", "config", "show", "--frontmatter", "false"]);
+        assert_eq!(overrides.frontmatter, Some(false));
+
+        let overrides = cli_overrides(vec!["test-bin", "--locale", "en", "config", "show"]);
+        assert_eq!(overrides.locale, Some(Locale::En));
+        assert!(Cli::try_parse_from(vec!["test-bin", "--locale", "fr", "config", "show"]).is_err());
+
+        let result = Cli::try_parse_from(vec!["test-bin", "config", "show", "--start-time", "8"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn subcommand_is_required() {
+        let result = Cli::try_parse_from(vec!["test-bin", "-y", "2025", "-m", "5"]);
+        assert!(result.is_err());
+    }
+
     // #[test]
     // #[should_panic(
     //     expected = "error: unexpected argument found"
@@ -106,6 +1110,6 @@ mod tests {
     #[test]
     fn verify_args() {
         use clap::CommandFactory;
-        Args::command().debug_assert();
+        Cli::command().debug_assert();
     }
 }
@@ -0,0 +1,231 @@
+use crate::parser::{DayBlock, MdParser, TodoStatus};
+use chrono::NaiveDate;
+
+/// How a day of the month was resolved by `merge_month`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Day up to today, kept as it is.
+    KeptPast,
+    /// Day with checked, cancelled or deferred items, kept as it is.
+    KeptChecked,
+    /// Day edited by hand whose template did not change, kept as it is.
+    KeptEdited,
+    /// Day re-rendered from the new templates.
+    Updated,
+    /// Day already identical to the new templates.
+    Unchanged,
+    /// Day edited by hand whose template changed too; the edits were kept.
+    Conflict,
+}
+
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub days: Vec<(NaiveDate, Resolution)>,
+    /// Whether a base snapshot was available to tell manual edits from template output.
+    pub had_base: bool,
+}
+
+impl MergeReport {
+    pub fn conflicts(&self) -> Vec<NaiveDate> {
+        self.days
+            .iter()
+            .filter(|(_, resolution)| *resolution == Resolution::Conflict)
+            .map(|(date, _)| *date)
+            .collect()
+    }
+
+    pub fn count(&self, resolution: Resolution) -> usize {
+        self.days.iter().filter(|(_, r)| *r == resolution).count()
+    }
+}
+
+fn has_checked_items(block: &DayBlock) -> bool {
+    block
+        .content
+        .lines()
+        .any(|line| TodoStatus::parse_checkbox(line).is_some_and(|(status, _)| !status.is_open()))
+}
+
+fn same(a: &str, b: &str) -> bool {
+    a.trim_end() == b.trim_end()
+}
+
+fn find(blocks: &[DayBlock], date: NaiveDate) -> Option<&DayBlock> {
+    blocks.iter().find(|block| block.date == date)
+}
+
+/// Three-way merge of a month file.
+///
+/// `base` is the markdown generated last time, `ours` the file as edited since then and
+/// `theirs` the markdown rendered from the current templates. The header and every day
+/// only present in `ours` (including repeated dates) are kept as they are.
+pub fn merge_month(
+    base: Option<&str>,
+    ours: &str,
+    theirs: &str,
+    today: NaiveDate,
+) -> (String, MergeReport) {
+    let (ours_header, ours_days) = MdParser::split_days(ours);
+    let (_, theirs_days) = MdParser::split_days(theirs);
+    let base_days = base.map(|base| MdParser::split_days(base).1);
+
+    let mut report = MergeReport {
+        had_base: base_days.is_some(),
+        ..MergeReport::default()
+    };
+    let mut merged: Vec<(NaiveDate, &str)> = Vec::new();
+
+    for new in &theirs_days {
+        let Some(old) = find(&ours_days, new.date) else {
+            merged.push((new.date, &new.content));
+            report.days.push((new.date, Resolution::Updated));
+            continue;
+        };
+
+        let base_block = base_days.as_deref().and_then(|days| find(days, new.date));
+
+        let resolution = if new.date <= today {
+            Resolution::KeptPast
+        } else if has_checked_items(old) {
+            Resolution::KeptChecked
+        } else if same(&old.content, &new.content) {
+            Resolution::Unchanged
+        } else {
+            match base_block {
+                Some(base) if same(&base.content, &old.content) => Resolution::Updated,
+                Some(base) if same(&base.content, &new.content) => Resolution::KeptEdited,
+                _ => Resolution::Conflict,
+            }
+        };
+
+        if resolution == Resolution::Updated {
+            merged.push((new.date, &new.content));
+        } else {
+            merged.push((new.date, &old.content));
+        }
+        report.days.push((new.date, resolution));
+    }
+
+    for (index, block) in ours_days.iter().enumerate() {
+        let first_of_date = ours_days.iter().position(|b| b.date == block.date) == Some(index);
+        if !first_of_date || find(&theirs_days, block.date).is_none() {
+            merged.push((block.date, &block.content));
+        }
+    }
+
+    merged.sort_by_key(|(date, _)| *date);
+    let mut content = ours_header;
+    for (_, block) in merged {
+        content.push_str(block);
+    }
+
+    (content, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "# TODOS 202608\n\n---\n\n";
+
+    fn day(yyyymmdd: &str, tasks: &[&str]) -> String {
+        let mut block = format!("## {} - Día\n\n", yyyymmdd);
+        for task in tasks {
+            block.push_str(task);
+            block.push('\n');
+        }
+        block.push('\n');
+        block
+    }
+
+    fn month(days: &[String]) -> String {
+        format!("{}{}", HEADER, days.concat())
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 8, 2).unwrap()
+    }
+
+    #[test]
+    fn test_split_days_round_trips() {
+        let md = month(&[
+            day("20260801", &["- [ ] 1. Uno"]),
+            day("20260802", &["- [ ] 1. Dos"]),
+        ]);
+        let (header, blocks) = MdParser::split_days(&md);
+        assert_eq!(header, HEADER);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].date, NaiveDate::from_ymd_opt(2026, 8, 2).unwrap());
+        let joined: String = blocks.iter().map(|b| b.content.as_str()).collect();
+        assert_eq!(format!("{}{}", header, joined), md);
+    }
+
+    #[test]
+    fn test_past_days_are_kept() {
+        let base = month(&[day("20260801", &["- [ ] 1. Viejo"])]);
+        let ours = base.clone();
+        let theirs = month(&[day("20260801", &["- [ ] 1. Nuevo"])]);
+
+        let (merged, report) = merge_month(Some(&base), &ours, &theirs, today());
+        assert_eq!(merged, ours);
+        assert_eq!(report.days[0].1, Resolution::KeptPast);
+    }
+
+    #[test]
+    fn test_future_untouched_days_are_updated() {
+        let base = month(&[day("20260803", &["- [ ] 1. Viejo"])]);
+        let ours = base.clone();
+        let theirs = month(&[day("20260803", &["- [ ] 1. Nuevo"])]);
+
+        let (merged, report) = merge_month(Some(&base), &ours, &theirs, today());
+        assert_eq!(merged, theirs);
+        assert_eq!(report.count(Resolution::Updated), 1);
+    }
+
+    #[test]
+    fn test_checked_future_days_are_kept() {
+        let base = month(&[day("20260803", &["- [ ] 1. Viejo"])]);
+        let ours = month(&[day("20260803", &["- [x] 1. Viejo"])]);
+        let theirs = month(&[day("20260803", &["- [ ] 1. Nuevo"])]);
+
+        let (merged, report) = merge_month(Some(&base), &ours, &theirs, today());
+        assert_eq!(merged, ours);
+        assert_eq!(report.days[0].1, Resolution::KeptChecked);
+    }
+
+    #[test]
+    fn test_manual_edits_are_kept_and_conflicts_flagged() {
+        let base = month(&[
+            day("20260803", &["- [ ] 1. Viejo"]),
+            day("20260804", &["- [ ] 1. Igual"]),
+        ]);
+        let ours = month(&[
+            day("20260803", &["- [ ] 1. Viejo", "- [ ] 2. Mío"]),
+            day("20260804", &["- [ ] 1. Igual", "- [ ] 2. Mío"]),
+        ]);
+        let theirs = month(&[
+            day("20260803", &["- [ ] 1. Nuevo"]),
+            day("20260804", &["- [ ] 1. Igual"]),
+        ]);
+
+        let (merged, report) = merge_month(Some(&base), &ours, &theirs, today());
+        assert_eq!(merged, ours);
+        assert_eq!(report.days[0].1, Resolution::Conflict);
+        assert_eq!(report.days[1].1, Resolution::KeptEdited);
+        assert_eq!(
+            report.conflicts(),
+            vec![NaiveDate::from_ymd_opt(2026, 8, 3).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_without_base_differences_are_conflicts() {
+        let ours = month(&[day("20260803", &["- [ ] 1. Viejo"])]);
+        let theirs = month(&[day("20260803", &["- [ ] 1. Nuevo"])]);
+
+        let (merged, report) = merge_month(None, &ours, &theirs, today());
+        assert_eq!(merged, ours);
+        assert!(!report.had_base);
+        assert_eq!(report.count(Resolution::Conflict), 1);
+    }
+}
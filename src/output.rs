@@ -0,0 +1,346 @@
+use crate::locale::{FileKind, Locale};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// Default file name pattern of daily notes, the one used by Obsidian's Daily Notes plugin.
+pub const DEFAULT_DAILY_PATTERN: &str = "%Y-%m-%d";
+
+/// How the markdown of a planned period is laid out on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// A single `TODOS - YYYYMM.md` file with every day.
+    #[default]
+    Single,
+    /// One note per day, plus a `TODOS - YYYYMM.md` index note linking to each day.
+    Daily,
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Layout::Single => f.write_str("single"),
+            Layout::Daily => f.write_str("daily"),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "single" => Ok(Layout::Single),
+            "daily" => Ok(Layout::Daily),
+            _ => Err(format!("unknown layout '{}', expected single or daily", s)),
+        }
+    }
+}
+
+/// Where daily notes are written: a folder relative to the output directory and a
+/// chrono `strftime` pattern for the file name without extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyNotes {
+    pub folder: PathBuf,
+    pub pattern: String,
+}
+
+impl Default for DailyNotes {
+    fn default() -> Self {
+        Self {
+            folder: PathBuf::from("."),
+            pattern: DEFAULT_DAILY_PATTERN.to_string(),
+        }
+    }
+}
+
+impl DailyNotes {
+    /// Wiki link target of the note of `date`, e.g. `Daily/2026-08-01`.
+    pub fn link(&self, date: NaiveDate) -> String {
+        let mut link: String = self
+            .folder
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(format!("{}/", name.to_string_lossy())),
+                _ => None,
+            })
+            .collect();
+        link.push_str(&date.format(&self.pattern).to_string());
+        link
+    }
+}
+
+/// Checks that `pattern` is a valid chrono `strftime` pattern for daily note names,
+/// giving every day of a year a name of its own.
+pub fn parse_daily_pattern(pattern: &str) -> Result<String, String> {
+    let invalid = || format!("invalid daily note pattern '{}'", pattern);
+    if pattern.trim().is_empty() {
+        return Err(invalid());
+    }
+    let mut names: HashMap<String, NaiveDate> = HashMap::new();
+    let first = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    for date in first
+        .iter_days()
+        .take_while(|date| date.year() == first.year())
+    {
+        let mut name = String::new();
+        write!(name, "{}", date.format(pattern)).map_err(|_| invalid())?;
+        if let Some(other) = names.insert(name.clone(), date) {
+            return Err(format!(
+                "daily note pattern '{}' gives {} and {} the same name '{}', add the day and month",
+                pattern, other, date, name
+            ));
+        }
+    }
+    Ok(pattern.to_string())
+}
+
+/// Fails naming the first path given twice, e.g. two days sharing a daily note.
+pub fn check_distinct(paths: &[PathBuf]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    match paths.iter().find(|path| !seen.insert(*path)) {
+        Some(path) => Err(format!(
+            "{} would be written twice, the daily note pattern gives several days the same name",
+            path.display()
+        )),
+        None => Ok(()),
+    }
+}
+
+/// What to do when a file about to be generated already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverwriteMode {
+    /// Keep the existing file and fail.
+    #[default]
+    Refuse,
+    /// Replace the existing file.
+    Force,
+    /// Keep a timestamped copy of the existing file, then replace it.
+    Backup,
+}
+
+/// What happened to the previous contents of a generated file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Created,
+    Replaced,
+    BackedUp(PathBuf),
+}
+
+/// Error returned when generated files already exist and `OverwriteMode::Refuse` is active.
+#[derive(Debug)]
+pub struct AlreadyExists(pub Vec<PathBuf>);
+
+impl fmt::Display for AlreadyExists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths: Vec<String> = self.0.iter().map(|p| p.display().to_string()).collect();
+        write!(
+            f,
+            "{} already exists (use --force to replace it or --backup to keep a copy)",
+            paths.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for AlreadyExists {}
+
+/// Fails with `AlreadyExists` listing every existing path when `mode` is `Refuse`.
+pub fn check_existing(paths: &[PathBuf], mode: OverwriteMode) -> Result<(), AlreadyExists> {
+    if mode != OverwriteMode::Refuse {
+        return Ok(());
+    }
+    let existing: Vec<PathBuf> = paths.iter().filter(|p| p.exists()).cloned().collect();
+    if existing.is_empty() {
+        Ok(())
+    } else {
+        Err(AlreadyExists(existing))
+    }
+}
+
+/// Path of the backup copy of `path` taken at `timestamp`,
+/// e.g. `TODOS - 202608.md.20260815T093000.bak`.
+pub fn backup_path(path: &Path, timestamp: NaiveDateTime) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        "{}.{}.bak",
+        file_name,
+        timestamp.format("%Y%m%dT%H%M%S")
+    ))
+}
+
+/// Copies `path` to its backup taken at `timestamp`. When a backup of the same second
+/// already exists, a counter is added instead of replacing it,
+/// e.g. `TODOS - 202608.md.20260815T093000.1.bak`.
+pub fn backup_file(path: &Path, timestamp: NaiveDateTime) -> io::Result<PathBuf> {
+    let first = backup_path(path, timestamp);
+    let mut backup = first.clone();
+    for counter in 1.. {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&backup)
+        {
+            Ok(mut file) => {
+                io::copy(&mut fs::File::open(path)?, &mut file)?;
+                break;
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                backup = first.with_extension(format!("{}.bak", counter));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(backup)
+}
+
+/// Writes `contents` to `path` honouring `mode` and reports what happened to the old file.
+pub fn write_file(path: &Path, contents: &str, mode: OverwriteMode) -> io::Result<Outcome> {
+    let outcome = if !path.exists() {
+        Outcome::Created
+    } else {
+        match mode {
+            OverwriteMode::Refuse => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    AlreadyExists(vec![path.to_path_buf()]),
+                ));
+            }
+            OverwriteMode::Force => Outcome::Replaced,
+            OverwriteMode::Backup => {
+                Outcome::BackedUp(backup_file(path, Local::now().naive_local())?)
+            }
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(outcome)
+}
+
+/// Prints a line describing `outcome` for the file `path` of the given `kind`.
+pub fn report(locale: Locale, kind: FileKind, path: &Path, outcome: &Outcome) {
+    match outcome {
+        Outcome::Created => println!("{}", locale.file_created(kind, path)),
+        Outcome::Replaced => println!("{}", locale.file_replaced(kind, path)),
+        Outcome::BackedUp(backup) => println!("{}", locale.file_backed_up(kind, path, backup)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("todos-output-{}-{}", prefix, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_daily_note_links() {
+        let date = NaiveDate::from_ymd_opt(2026, 8, 1).unwrap();
+        assert_eq!(DailyNotes::default().link(date), "2026-08-01");
+        let notes = DailyNotes {
+            folder: PathBuf::from("./Diario/2026"),
+            pattern: "%Y%m%d".to_string(),
+        };
+        assert_eq!(notes.link(date), "Diario/2026/20260801");
+
+        assert!(parse_daily_pattern("%Y-%m-%d").is_ok());
+        assert!(parse_daily_pattern("%Q").is_err());
+        assert!(parse_daily_pattern("").is_err());
+        assert!(parse_daily_pattern("daily").is_err());
+        assert!(parse_daily_pattern("%Y-%m").is_err());
+        assert!(parse_daily_pattern("%A").is_err());
+        assert!(parse_daily_pattern("%d").is_err());
+        assert!(parse_daily_pattern("%m-%d").is_ok());
+        assert_eq!(
+            check_distinct(&[PathBuf::from("a.md"), PathBuf::from("b.md")]),
+            Ok(())
+        );
+        assert!(check_distinct(&[PathBuf::from("a.md"), PathBuf::from("a.md")]).is_err());
+        assert_eq!("Daily".parse::<Layout>(), Ok(Layout::Daily));
+        assert!("weekly".parse::<Layout>().is_err());
+    }
+
+    #[test]
+    fn test_backup_path_is_timestamped() {
+        let timestamp = NaiveDate::from_ymd_opt(2026, 8, 15)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        let backup = backup_path(Path::new("/tmp/TODOS - 202608.md"), timestamp);
+        assert_eq!(
+            backup,
+            PathBuf::from("/tmp/TODOS - 202608.md.20260815T093000.bak")
+        );
+    }
+
+    #[test]
+    fn test_backups_of_the_same_second_are_kept() {
+        let dir = temp_dir("same-second");
+        let path = dir.join("TODOS - 202608.md");
+        let timestamp = NaiveDate::from_ymd_opt(2026, 8, 15)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+
+        fs::write(&path, "uno").unwrap();
+        let first = backup_file(&path, timestamp).unwrap();
+        fs::write(&path, "dos").unwrap();
+        let second = backup_file(&path, timestamp).unwrap();
+        assert_eq!(first, backup_path(&path, timestamp));
+        assert_eq!(second, dir.join("TODOS - 202608.md.20260815T093000.1.bak"));
+        assert_eq!(fs::read_to_string(&first).unwrap(), "uno");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "dos");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_refuse_keeps_existing_file() {
+        let dir = temp_dir("refuse");
+        let path = dir.join("TODOS - 202608.md");
+        fs::write(&path, "notas").unwrap();
+
+        assert!(check_existing(std::slice::from_ref(&path), OverwriteMode::Refuse).is_err());
+        assert!(check_existing(std::slice::from_ref(&path), OverwriteMode::Force).is_ok());
+        let err = write_file(&path, "nuevo", OverwriteMode::Refuse).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "notas");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_force_and_backup_outcomes() {
+        let dir = temp_dir("outcomes");
+        let path = dir.join("TODOS - 202608.md");
+
+        let outcome = write_file(&path, "uno", OverwriteMode::Refuse).unwrap();
+        assert_eq!(outcome, Outcome::Created);
+
+        let outcome = write_file(&path, "dos", OverwriteMode::Force).unwrap();
+        assert_eq!(outcome, Outcome::Replaced);
+
+        let Outcome::BackedUp(backup) = write_file(&path, "tres", OverwriteMode::Backup).unwrap()
+        else {
+            panic!("expected a backup");
+        };
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "dos");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "tres");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,73 +1,495 @@
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::frontmatter;
+use crate::locale::{Locale, parse_weekday};
+use chrono::Datelike;
+use chrono::Duration;
 use chrono::NaiveDate;
 use chrono::NaiveTime;
+use chrono::Weekday;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TodoItem {
     pub date: NaiveDate,
+    /// Weekday as written in the day heading, empty when left out. The weekday used
+    /// for rule lookups is always the one of `date`.
     pub weekday_name: String,
     pub priority: u8,
     pub description: String,
+    pub status: TodoStatus,
+    /// Start time written in the description, e.g. `@14:30`.
+    pub start: Option<NaiveTime>,
+    /// End time of a range written in the description, e.g. `09:00-17:00`.
+    pub end: Option<NaiveTime>,
+    /// Length written in the description, e.g. `2 horas` or `1h30`.
+    pub duration: Option<Duration>,
+}
+
+/// State of a todo item, from its checkbox.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TodoStatus {
+    /// `- [ ]`
+    #[default]
+    Open,
+    /// `- [x]` or `- [X]`
+    Done,
+    /// `- [-]`
+    Cancelled,
+    /// `- [>]`, moved to another day.
+    Deferred,
+}
+
+impl TodoStatus {
+    /// Splits a task line into its status and the text after the checkbox.
+    pub fn parse_checkbox(line: &str) -> Option<(Self, &str)> {
+        let rest = line.strip_prefix("- [")?;
+        let status = match rest.chars().next()? {
+            ' ' => TodoStatus::Open,
+            'x' | 'X' => TodoStatus::Done,
+            '-' => TodoStatus::Cancelled,
+            '>' => TodoStatus::Deferred,
+            _ => return None,
+        };
+        Some((status, rest[1..].strip_prefix("] ")?))
+    }
+
+    /// Checkbox of the status, e.g. `[x]`.
+    pub fn checkbox(self) -> &'static str {
+        match self {
+            TodoStatus::Open => "[ ]",
+            TodoStatus::Done => "[x]",
+            TodoStatus::Cancelled => "[-]",
+            TodoStatus::Deferred => "[>]",
+        }
+    }
+
+    pub fn is_open(self) -> bool {
+        self == TodoStatus::Open
+    }
+}
+
+/// Link from an index note to a daily note, with the day it plans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyNoteLink {
+    /// Link target, the note path without `.md` relative to the index note.
+    pub target: String,
+    pub date: NaiveDate,
+    pub weekday_name: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DueTimeRule {
-    pub weekday: String,
+    pub weekday: Weekday,
     pub priority: u8,
     pub hour: NaiveTime,
     pub alarm_minutes: u16,
 }
 
 impl DueTimeRule {
-    pub fn lookup<'a>(
-        rules: &'a [DueTimeRule],
-        weekday: &str,
-        priority: u8,
-    ) -> Option<&'a DueTimeRule> {
+    /// Finds the rule for `weekday` and `priority`.
+    pub fn lookup(rules: &[DueTimeRule], weekday: Weekday, priority: u8) -> Option<&DueTimeRule> {
         rules
             .iter()
             .find(|r| r.weekday == weekday && r.priority == priority)
     }
 }
 
+/// A `## YYYYMMDD - Weekday` section of a TODOS file, heading line included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayBlock {
+    pub date: NaiveDate,
+    pub content: String,
+}
+
+/// Reads a time of day at the start of `text`: `9:30`, `14:30`, `14h` or `14h30`.
+/// Hours followed by `h` need two digits, so that `2h` reads as a duration, unless
+/// the time is marked with `at`.
+fn clock(text: &str, at: bool) -> Option<(NaiveTime, &str)> {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if !(1..=2).contains(&digits) {
+        return None;
+    }
+    let two_digits = |text: &str| {
+        text.get(..2)
+            .filter(|minute| minute.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|minute| minute.parse::<u32>().ok())
+    };
+    let hour: u32 = text[..digits].parse().ok()?;
+    let rest = &text[digits..];
+    let (minute, rest) = if let Some(rest) = rest.strip_prefix(':') {
+        (two_digits(rest)?, &rest[2..])
+    } else if let Some(rest) = rest.strip_prefix('h').filter(|_| digits == 2 || at) {
+        match two_digits(rest) {
+            Some(minute) => (minute, &rest[2..]),
+            None => (0, rest),
+        }
+    } else {
+        return None;
+    };
+    if rest.starts_with(char::is_alphanumeric) {
+        return None;
+    }
+    Some((NaiveTime::from_hms_opt(hour, minute, 0)?, rest))
+}
+
+/// First time written in a task description, with the end time when it is a range:
+/// `09:00-17:00`, `@14:30`, `a las 14h`.
+fn inline_times(text: &str) -> (Option<NaiveTime>, Option<NaiveTime>) {
+    let mut previous: Option<char> = None;
+    for (index, c) in text.char_indices() {
+        let boundary = previous.is_none_or(|p| !p.is_alphanumeric() && p != ':');
+        previous = Some(c);
+        if !boundary {
+            continue;
+        }
+        let rest = &text[index..];
+        let (at, rest) = match rest.strip_prefix('@') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let Some((start, rest)) = clock(rest, at) else {
+            continue;
+        };
+        let end = rest
+            .trim_start()
+            .strip_prefix(['-', '–'])
+            .and_then(|rest| clock(rest.trim_start(), true))
+            .map(|(end, _)| end);
+        return (Some(start), end);
+    }
+    (None, None)
+}
+
+fn number(text: &str) -> Option<(u32, &str)> {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    Some((text[..digits].parse().ok()?, &text[digits..]))
+}
+
+/// Reads a duration unit at the start of `text`, `true` for hours and `false` for minutes.
+fn unit(text: &str) -> Option<(bool, &str)> {
+    let len = text.len() - text.trim_start_matches(char::is_alphabetic).len();
+    let hours = match text[..len].to_lowercase().as_str() {
+        "h" | "hr" | "hrs" | "hora" | "horas" | "hour" | "hours" => true,
+        "m" | "min" | "mins" | "minuto" | "minutos" | "minute" | "minutes" => false,
+        _ => return None,
+    };
+    Some((hours, &text[len..]))
+}
+
+/// Reads a duration at the start of `text`: `2 horas`, `30 min`, `45m`, `1h30` or
+/// `1 hour 30 minutes`. Times of day such as `14h` are not durations.
+fn duration(text: &str) -> Option<Duration> {
+    if clock(text, false).is_some() {
+        return None;
+    }
+    let (amount, rest) = number(text)?;
+    let (hours, rest) = unit(rest.trim_start())?;
+    if !hours {
+        return Some(Duration::minutes(amount.into()));
+    }
+    let minutes = number(rest.trim_start())
+        .and_then(|(minutes, after)| match unit(after.trim_start()) {
+            Some((false, _)) => Some(minutes),
+            // `1h30`
+            None if !rest.starts_with(' ') => Some(minutes),
+            _ => None,
+        })
+        .unwrap_or(0);
+    Some(Duration::minutes(
+        i64::from(amount) * 60 + i64::from(minutes),
+    ))
+}
+
+/// Longest duration a task can take; longer ones are ignored.
+const MAX_DURATION_HOURS: i64 = 24;
+
+/// First duration written in a task description, e.g. `Trabajar en Ematrix, 2 horas`.
+fn inline_duration(text: &str) -> Option<Duration> {
+    let mut previous: Option<char> = None;
+    for (index, c) in text.char_indices() {
+        let boundary = previous.is_none_or(|p| p.is_whitespace() || p == '(' || p == '[');
+        previous = Some(c);
+        if let Some(duration) = duration(&text[index..]).filter(|_| boundary)
+            && duration > Duration::zero()
+        {
+            return Some(duration);
+        }
+    }
+    None
+}
+
 pub struct MdParser;
 
 impl MdParser {
+    /// Splits a TODOS file into the text before the first day heading and one block per day.
+    /// Concatenating the header and every block's content gives back the original text.
+    pub fn split_days(content: &str) -> (String, Vec<DayBlock>) {
+        let mut header = String::new();
+        let mut blocks: Vec<DayBlock> = Vec::new();
+
+        for line in content.split_inclusive('\n') {
+            let heading_date = line
+                .strip_prefix("## ")
+                .and_then(|rest| rest.get(..8))
+                .and_then(|yyyymmdd| NaiveDate::parse_from_str(yyyymmdd, "%Y%m%d").ok());
+
+            match (heading_date, blocks.last_mut()) {
+                (Some(date), _) => blocks.push(DayBlock {
+                    date,
+                    content: line.to_string(),
+                }),
+                (None, Some(block)) => block.content.push_str(line),
+                (None, None) => header.push_str(line),
+            }
+        }
+
+        (header, blocks)
+    }
+
+    /// Inserts `- [ ] N. Task` lines into a day, each before the first task of a higher
+    /// priority, or after the last task.
+    pub fn insert_tasks(content: &str, tasks: &[(u8, String)]) -> String {
+        let task_priority = |line: &str| {
+            let (_, rest) = TodoStatus::parse_checkbox(line)?;
+            let (priority, _) = rest.split_once(". ")?;
+            priority.parse::<u8>().ok()
+        };
+        let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+        for (priority, description) in tasks {
+            let position = lines
+                .iter()
+                .position(|line| task_priority(line).is_some_and(|p| p > *priority))
+                .or_else(|| {
+                    lines
+                        .iter()
+                        .rposition(|line| task_priority(line).is_some())
+                        .map(|last| last + 1)
+                })
+                .unwrap_or(lines.len());
+            if let Some(previous) = position.checked_sub(1).map(|index| &mut lines[index])
+                && !previous.ends_with('\n')
+            {
+                previous.push('\n');
+            }
+            lines.insert(position, format!("- [ ] {}. {}\n", priority, description));
+        }
+        lines.concat()
+    }
+
+    /// Parses the todo items of a TODOS file, whatever their status, skipping any
+    /// YAML frontmatter.
     pub fn parse(content: &str) -> Vec<TodoItem> {
+        Self::parse_with_diagnostics(content).0
+    }
+
+    /// Parses the todo items of a TODOS file with the problems found in its lines.
+    pub fn parse_with_diagnostics(content: &str) -> (Vec<TodoItem>, Vec<Diagnostic>) {
+        Self::parse_from(content, None)
+    }
+
+    /// Parses a daily note linked from an index note. Items before the first day
+    /// heading, or in a note without one, belong to the day of the link.
+    pub fn parse_daily_note(link: &DailyNoteLink, content: &str) -> Vec<TodoItem> {
+        Self::parse_daily_note_with_diagnostics(link, content).0
+    }
+
+    pub fn parse_daily_note_with_diagnostics(
+        link: &DailyNoteLink,
+        content: &str,
+    ) -> (Vec<TodoItem>, Vec<Diagnostic>) {
+        Self::parse_from(content, Some((link.date, link.weekday_name.clone())))
+    }
+
+    /// Daily notes linked from an index note, from lines like
+    /// `- [[Daily/2026-08-01|20260801 - Sábado]]`.
+    pub fn daily_note_links(content: &str) -> Vec<DailyNoteLink> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let link = line.trim().strip_prefix("- [[")?.strip_suffix("]]")?;
+                let (target, alias) = link.split_once('|')?;
+                let (date, weekday_name) = alias.split_once(" - ")?;
+                Some(DailyNoteLink {
+                    target: target.to_string(),
+                    date: NaiveDate::parse_from_str(date, "%Y%m%d").ok()?,
+                    weekday_name: weekday_name.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_from(
+        content: &str,
+        day: Option<(NaiveDate, String)>,
+    ) -> (Vec<TodoItem>, Vec<Diagnostic>) {
         let mut items = Vec::new();
-        let mut current_date: Option<NaiveDate> = None;
-        let mut current_weekday: Option<String> = None;
+        let mut diagnostics = Vec::new();
+        let (mut current_date, mut current_weekday) = day.unzip();
+        // Inside a day whose heading was reported, whose lines are skipped quietly.
+        let mut rejected = false;
+
+        let body = frontmatter::strip(content);
+        let frontmatter_lines = content[..content.len() - body.len()].lines().count();
+        for (index, line) in body.lines().enumerate() {
+            let line_number = frontmatter_lines + index + 1;
+            let mut report = |severity, offset, message: String| {
+                diagnostics.push(Diagnostic::new(
+                    severity,
+                    line_number,
+                    line,
+                    offset,
+                    message,
+                ))
+            };
 
-        for line in content.lines() {
             if let Some(rest) = line.strip_prefix("## ") {
-                let parts: Vec<&str> = rest.splitn(2, " - ").collect();
-                if parts.len() == 2
-                    && let Ok(date) = NaiveDate::parse_from_str(parts[0], "%Y%m%d")
-                {
-                    current_date = Some(date);
-                    current_weekday = Some(parts[1].to_string());
+                let date = rest
+                    .get(..8)
+                    .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok());
+                // The weekday text is optional: `## 20260801` or `## 20260801 - Sábado`.
+                let text = rest.get(8..).and_then(|after| match after.trim_end() {
+                    "" => Some(""),
+                    after => after.strip_prefix(" - "),
+                });
+                match (date, text) {
+                    (Some(date), Some(text)) => {
+                        let weekday = text.trim_end();
+                        if !weekday.is_empty() && parse_weekday(weekday) != Some(date.weekday()) {
+                            report(
+                                Severity::Warning,
+                                line.len() - text.len(),
+                                format!(
+                                    "'{}' is not the weekday of {}, using {}",
+                                    weekday,
+                                    date.format("%Y%m%d"),
+                                    Locale::En.weekday_name(date.weekday())
+                                ),
+                            );
+                        }
+                        current_date = Some(date);
+                        current_weekday = Some(weekday.to_string());
+                        rejected = false;
+                    }
+                    (Some(_), None) => {
+                        report(
+                            Severity::Error,
+                            11,
+                            String::from(
+                                "expected ` - Weekday` after the date, its tasks are skipped",
+                            ),
+                        );
+                        (current_date, current_weekday) = (None, None);
+                        rejected = true;
+                    }
+                    // Any heading starting with a digit is meant to be a day.
+                    (None, _) if rest.starts_with(|c: char| c.is_ascii_digit()) => {
+                        let date = rest.split_whitespace().next().unwrap_or_default();
+                        report(
+                            Severity::Error,
+                            3,
+                            format!("invalid date '{}', its tasks are skipped", date),
+                        );
+                        (current_date, current_weekday) = (None, None);
+                        rejected = true;
+                    }
+                    (None, _) => {}
                 }
                 continue;
             }
+            if rejected {
+                continue;
+            }
 
-            if let Some(rest) = line.strip_prefix("- [ ] ")
-                && let (Some(date), Some(ref weekday)) = (current_date, current_weekday.as_ref())
-            {
-                let todo_parts: Vec<&str> = rest.splitn(2, ". ").collect();
-                if todo_parts.len() == 2 {
-                    let priority: u8 = todo_parts[0].parse().unwrap_or(6).clamp(1, 6);
-                    let description = todo_parts[1].trim().to_string();
-                    let description = description.replace("[[", "").replace("]]", "");
-                    items.push(TodoItem {
-                        date,
-                        weekday_name: weekday.to_string(),
-                        priority,
-                        description,
-                    });
+            let Some((status, rest)) = TodoStatus::parse_checkbox(line) else {
+                if let Some(checkbox) = line
+                    .strip_prefix("- [")
+                    .and_then(|rest| rest.get(..3))
+                    .filter(|checkbox| checkbox.ends_with("] "))
+                {
+                    report(
+                        Severity::Error,
+                        2,
+                        format!(
+                            "unknown checkbox '[{}', expected [ ], [x], [-] or [>], skipped",
+                            checkbox.trim_end()
+                        ),
+                    );
                 }
-            }
+                continue;
+            };
+            let offset = line.len() - rest.len();
+            let (Some(date), Some(weekday)) = (current_date, current_weekday.as_ref()) else {
+                report(
+                    Severity::Error,
+                    0,
+                    String::from("task outside of a day, skipped"),
+                );
+                continue;
+            };
+            let Some((priority, description)) = rest.split_once(". ") else {
+                report(
+                    Severity::Error,
+                    offset,
+                    String::from("expected a priority like `1. ` before the task, skipped"),
+                );
+                continue;
+            };
+            let numeric = !priority.is_empty() && priority.bytes().all(|b| b.is_ascii_digit());
+            let priority: u8 = match priority.parse::<u8>() {
+                Ok(value @ 1..=6) => value,
+                // Any other number, however long, is out of range rather than invalid.
+                _ if numeric => {
+                    let clamped = if priority.bytes().all(|b| b == b'0') {
+                        1
+                    } else {
+                        6
+                    };
+                    report(
+                        Severity::Warning,
+                        offset,
+                        format!("priority {} outside 1 to 6, using {}", priority, clamped),
+                    );
+                    clamped
+                }
+                _ => {
+                    report(
+                        Severity::Warning,
+                        offset,
+                        format!("invalid priority '{}', using 6", priority),
+                    );
+                    6
+                }
+            };
+            let description_offset = line.len() - description.trim_start().len();
+            let description = description.trim().replace("[[", "").replace("]]", "");
+            let (start, end) = inline_times(&description);
+            let duration = inline_duration(&description).filter(|duration| {
+                let too_long = *duration > Duration::hours(MAX_DURATION_HOURS);
+                if too_long {
+                    report(
+                        Severity::Warning,
+                        description_offset,
+                        format!(
+                            "duration of {} minutes over {} hours, ignored",
+                            duration.num_minutes(),
+                            MAX_DURATION_HOURS
+                        ),
+                    );
+                }
+                !too_long
+            });
+            items.push(TodoItem {
+                date,
+                weekday_name: weekday.to_string(),
+                priority,
+                description,
+                status,
+                start,
+                end,
+                duration,
+            });
         }
 
-        items
+        (items, diagnostics)
     }
 }
 
@@ -75,24 +497,88 @@ pub struct CsvParser;
 
 impl CsvParser {
     pub fn parse(content: &str) -> Vec<DueTimeRule> {
+        Self::parse_with_diagnostics(content).0
+    }
+
+    /// Parses a `weekday,priority,hour,minutes` CSV with a header row, with the
+    /// problems found in its lines. Malformed rows are skipped.
+    pub fn parse_with_diagnostics(content: &str) -> (Vec<DueTimeRule>, Vec<Diagnostic>) {
         let mut rules = Vec::new();
+        let mut diagnostics = Vec::new();
 
-        for line in content.lines().skip(1) {
+        for (index, line) in content.lines().enumerate().skip(1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut report = |severity, offset, message: String| {
+                diagnostics.push(Diagnostic::new(severity, index + 1, line, offset, message))
+            };
             let parts: Vec<&str> = line.split(',').collect();
             if parts.len() != 4 {
+                report(
+                    Severity::Error,
+                    0,
+                    format!(
+                        "expected 4 fields (weekday,priority,hour,minutes), found {}, skipped",
+                        parts.len()
+                    ),
+                );
                 continue;
             }
+            // Byte offset of each field in the line.
+            let offsets: Vec<usize> = parts
+                .iter()
+                .scan(0, |offset, part| {
+                    let start = *offset;
+                    *offset += part.len() + 1;
+                    Some(start)
+                })
+                .collect();
 
-            let weekday = parts[0].trim().to_string();
+            let Some(weekday) = parse_weekday(parts[0]) else {
+                report(
+                    Severity::Error,
+                    offsets[0],
+                    format!("unknown weekday '{}', skipped", parts[0].trim()),
+                );
+                continue;
+            };
             let priority: u8 = match parts[1].trim().parse() {
                 Ok(p) if (1..=6).contains(&p) => p,
-                _ => continue,
+                _ => {
+                    report(
+                        Severity::Error,
+                        offsets[1],
+                        format!(
+                            "invalid priority '{}', expected 1 to 6, skipped",
+                            parts[1].trim()
+                        ),
+                    );
+                    continue;
+                }
             };
-            let hour = match NaiveTime::parse_from_str(parts[2].trim(), "%H:%M") {
-                Ok(h) => h,
-                _ => continue,
+            let Ok(hour) = NaiveTime::parse_from_str(parts[2].trim(), "%H:%M") else {
+                report(
+                    Severity::Error,
+                    offsets[2],
+                    format!(
+                        "invalid hour '{}', expected HH:MM, skipped",
+                        parts[2].trim()
+                    ),
+                );
+                continue;
+            };
+            let alarm_minutes: u16 = match parts[3].trim() {
+                "" => 0,
+                minutes => minutes.parse().unwrap_or_else(|_| {
+                    report(
+                        Severity::Warning,
+                        offsets[3],
+                        format!("invalid minutes '{}', using 0", minutes),
+                    );
+                    0
+                }),
             };
-            let alarm_minutes: u16 = parts[3].trim().parse().unwrap_or_default();
 
             rules.push(DueTimeRule {
                 weekday,
@@ -102,6 +588,6 @@ impl CsvParser {
             });
         }
 
-        rules
+        (rules, diagnostics)
     }
 }
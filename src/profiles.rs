@@ -0,0 +1,130 @@
+use chrono::NaiveDate;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Date range, e.g. a trip, whose days use their own templates and due-time rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    /// First day of the range.
+    pub from: NaiveDate,
+    /// Last day of the range, included.
+    pub to: NaiveDate,
+    /// Template rendering every day of the range, e.g. `travel.md`.
+    pub template: Option<String>,
+    /// Directory replacing the templates directory for the range.
+    pub templates_dir: Option<PathBuf>,
+    /// Due-time rules CSV of the range, `None` for no reminders at all.
+    pub rules: Option<PathBuf>,
+}
+
+fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", text))
+}
+
+impl Profile {
+    /// Profile named `name` from `from` to `to`, both `YYYY-MM-DD`, without templates or rules.
+    pub fn new(name: &str, from: &str, to: &str) -> Result<Self, String> {
+        let (from, to) = (parse_date(from)?, parse_date(to)?);
+        if to < from {
+            return Err(format!("profile '{}' ends before it starts", name));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            from,
+            to,
+            template: None,
+            templates_dir: None,
+            rules: None,
+        })
+    }
+
+    /// Parses `TEMPLATE:FROM:TO`, as given to `--profile`, e.g.
+    /// `travel.md:2026-08-03:2026-08-09`. The profile is named after the template
+    /// and has no due-time rules.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid profile '{}', expected TEMPLATE:FROM:TO", text);
+        let mut parts = text.trim().split(':');
+        let (Some(template), Some(from), Some(to), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        if template.is_empty() {
+            return Err(invalid());
+        }
+        let name = Path::new(template)
+            .file_stem()
+            .map_or(template.to_string(), |stem| {
+                stem.to_string_lossy().to_string()
+            });
+        Ok(Self {
+            template: Some(template.to_string()),
+            ..Self::new(&name, from, to)?
+        })
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.from..=self.to).contains(&date)
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}..{}",
+            self.name,
+            self.from.format("%Y-%m-%d"),
+            self.to.format("%Y-%m-%d")
+        )
+    }
+}
+
+/// Profiles in the order they were defined.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profiles(pub Vec<Profile>);
+
+impl Profiles {
+    /// First profile whose range contains `date`.
+    pub fn lookup(&self, date: NaiveDate) -> Option<&Profile> {
+        self.0.iter().find(|profile| profile.contains(date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_flag() {
+        let profile = Profile::parse("travel.md:2026-08-03:2026-08-09").unwrap();
+        assert_eq!(profile.name, "travel");
+        assert_eq!(profile.template.as_deref(), Some("travel.md"));
+        assert_eq!(profile.rules, None);
+        assert_eq!(profile.to_string(), "travel 2026-08-03..2026-08-09");
+
+        assert!(Profile::parse("travel.md:2026-08-03").is_err());
+        assert!(Profile::parse(":2026-08-03:2026-08-09").is_err());
+        assert!(Profile::parse("travel.md:2026-08-09:2026-08-03").is_err());
+        assert!(Profile::parse("travel.md:2026-08-03:agosto").is_err());
+    }
+
+    #[test]
+    fn test_lookup_includes_both_ends() {
+        let profiles = Profiles(vec![
+            Profile::parse("travel.md:2026-08-03:2026-08-09").unwrap(),
+            Profile::parse("beach.md:2026-08-09:2026-08-12").unwrap(),
+        ]);
+        assert_eq!(profiles.lookup(date(2026, 8, 2)), None);
+        assert_eq!(profiles.lookup(date(2026, 8, 3)).unwrap().name, "travel");
+        assert_eq!(profiles.lookup(date(2026, 8, 9)).unwrap().name, "travel");
+        assert_eq!(profiles.lookup(date(2026, 8, 12)).unwrap().name, "beach");
+        assert_eq!(profiles.lookup(date(2026, 8, 13)), None);
+    }
+}
@@ -0,0 +1,327 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::diagnostics::{self, Diagnostic, Severity};
+use crate::locale::parse_weekday;
+use crate::parser::MdParser;
+
+/// File name of the recurring tasks looked up inside a templates directory.
+pub const RECURRING_FILE: &str = "recurring.csv";
+
+/// Days a recurring task falls on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    /// `every friday`
+    Weekly(Weekday),
+    /// `every 2 weeks on tuesday from 2026-08-04`: every `interval` weeks, counted
+    /// from the week of `start`.
+    EveryWeeks {
+        interval: u32,
+        weekday: Weekday,
+        start: NaiveDate,
+    },
+    /// `second tuesday` or `2nd tuesday`: the nth (1 to 5) weekday of the month.
+    NthWeekday { n: u32, weekday: Weekday },
+    /// `last friday`
+    LastWeekday(Weekday),
+    /// `day 1`: a day of the month, skipped by shorter months.
+    MonthDay(u32),
+    /// `last day`
+    LastDay,
+}
+
+fn ordinal(text: &str) -> Option<u32> {
+    match text {
+        "first" | "1st" | "1" => Some(1),
+        "second" | "2nd" | "2" => Some(2),
+        "third" | "3rd" | "3" => Some(3),
+        "fourth" | "4th" | "4" => Some(4),
+        "fifth" | "5th" | "5" => Some(5),
+        _ => None,
+    }
+}
+
+impl Recurrence {
+    /// Parses a recurrence expression. Keywords are English; weekdays may be written
+    /// in any supported locale, e.g. `last viernes`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid recurrence '{}'", text.trim());
+        let weekday = |name: &str| parse_weekday(name).ok_or_else(invalid);
+        let lowered = text.to_lowercase();
+        let tokens: Vec<&str> = lowered.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["every", name] => Ok(Recurrence::Weekly(weekday(name)?)),
+            [
+                "every",
+                interval,
+                "week" | "weeks",
+                "on",
+                name,
+                "from",
+                start,
+            ] => {
+                let interval: u32 = interval.parse().map_err(|_| invalid())?;
+                let start = NaiveDate::parse_from_str(start, "%Y-%m-%d").map_err(|_| invalid())?;
+                if interval == 0 {
+                    return Err(invalid());
+                }
+                Ok(Recurrence::EveryWeeks {
+                    interval,
+                    weekday: weekday(name)?,
+                    start,
+                })
+            }
+            ["day", day] => match day.parse() {
+                Ok(day) if (1..=31).contains(&day) => Ok(Recurrence::MonthDay(day)),
+                _ => Err(invalid()),
+            },
+            ["last", "day"] => Ok(Recurrence::LastDay),
+            ["last", name] => Ok(Recurrence::LastWeekday(weekday(name)?)),
+            [n, name] => Ok(Recurrence::NthWeekday {
+                n: ordinal(n).ok_or_else(invalid)?,
+                weekday: weekday(name)?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        let is_last_day = || {
+            date.succ_opt()
+                .is_none_or(|next| next.month() != date.month())
+        };
+        match *self {
+            Recurrence::Weekly(weekday) => date.weekday() == weekday,
+            Recurrence::EveryWeeks {
+                interval,
+                weekday,
+                start,
+            } => {
+                let monday = |date: NaiveDate| {
+                    date - Duration::days(date.weekday().num_days_from_monday().into())
+                };
+                let weeks = (monday(date) - monday(start)).num_days() / 7;
+                date.weekday() == weekday && date >= start && weeks % i64::from(interval) == 0
+            }
+            Recurrence::NthWeekday { n, weekday } => {
+                date.weekday() == weekday && (date.day() - 1) / 7 + 1 == n
+            }
+            Recurrence::LastWeekday(weekday) => {
+                date.weekday() == weekday && (date + Duration::days(7)).month() != date.month()
+            }
+            Recurrence::MonthDay(day) => date.day() == day,
+            Recurrence::LastDay => is_last_day(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringTask {
+    pub recurrence: Recurrence,
+    /// Ivy Lee priority, 1 to 6.
+    pub priority: u8,
+    pub description: String,
+}
+
+/// Tasks added to the days their recurrence matches, besides the template content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecurringTasks(pub Vec<RecurringTask>);
+
+impl RecurringTasks {
+    pub fn parse_csv(content: &str) -> Self {
+        Self::parse_csv_with_diagnostics(content).0
+    }
+
+    /// Parses a `rule,priority,description` CSV with a header row, with the problems
+    /// found in its lines. The description may contain commas. Malformed lines are
+    /// skipped.
+    pub fn parse_csv_with_diagnostics(content: &str) -> (Self, Vec<Diagnostic>) {
+        let mut tasks = Vec::new();
+        let mut diagnostics = Vec::new();
+        for (index, line) in content.lines().enumerate().skip(1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut report = |offset, message: String| {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    index + 1,
+                    line,
+                    offset,
+                    message,
+                ))
+            };
+            let parts: Vec<&str> = line.splitn(3, ',').collect();
+            let [rule, priority, description] = parts.as_slice() else {
+                report(
+                    0,
+                    format!(
+                        "expected 3 fields (rule,priority,description), found {}, skipped",
+                        parts.len()
+                    ),
+                );
+                continue;
+            };
+            // Byte offsets of the priority and the description in the line.
+            let priority_offset = rule.len() + 1;
+            let description_offset = priority_offset + priority.len() + 1;
+
+            let recurrence = match Recurrence::parse(rule) {
+                Ok(recurrence) => recurrence,
+                Err(e) => {
+                    report(0, format!("{}, skipped", e));
+                    continue;
+                }
+            };
+            let priority: u8 = match priority.trim().parse() {
+                Ok(p) if (1..=6).contains(&p) => p,
+                _ => {
+                    report(
+                        priority_offset,
+                        format!(
+                            "invalid priority '{}', expected 1 to 6, skipped",
+                            priority.trim()
+                        ),
+                    );
+                    continue;
+                }
+            };
+            let description = description.trim();
+            if description.is_empty() {
+                report(
+                    description_offset,
+                    String::from("empty description, skipped"),
+                );
+                continue;
+            }
+            tasks.push(RecurringTask {
+                recurrence,
+                priority,
+                description: description.to_string(),
+            });
+        }
+        (RecurringTasks(tasks), diagnostics)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(Self::load_with_diagnostics(path)?.0)
+    }
+
+    /// Reads a recurring tasks CSV, with the problems found in its lines.
+    pub fn load_with_diagnostics(path: &Path) -> io::Result<(Self, Vec<Diagnostic>)> {
+        let (tasks, diagnostics) = Self::parse_csv_with_diagnostics(&fs::read_to_string(path)?);
+        Ok((tasks, diagnostics::in_file(diagnostics, path)))
+    }
+
+    /// Inserts the tasks falling on `date` into a rendered day, in priority order.
+    pub fn insert(&self, date: NaiveDate, content: &str) -> String {
+        let tasks: Vec<(u8, String)> = self
+            .0
+            .iter()
+            .filter(|task| task.recurrence.matches(date))
+            .map(|task| (task.priority, task.description.clone()))
+            .collect();
+        MdParser::insert_tasks(content, &tasks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn days_matching(rule: &str, year: i32, month: u32) -> Vec<u32> {
+        let recurrence = Recurrence::parse(rule).unwrap();
+        date(year, month, 1)
+            .iter_days()
+            .take_while(|day| day.month() == month)
+            .filter(|day| recurrence.matches(*day))
+            .map(|day| day.day())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        assert_eq!(days_matching("every friday", 2026, 8), vec![7, 14, 21, 28]);
+        assert_eq!(days_matching("last Friday", 2026, 8), vec![28]);
+        assert_eq!(days_matching("last viernes", 2026, 7), vec![31]);
+        assert_eq!(days_matching("second tuesday", 2026, 8), vec![11]);
+        assert_eq!(days_matching("1st Lunes", 2026, 8), vec![3]);
+        assert_eq!(days_matching("fifth monday", 2026, 8), vec![31]);
+        assert_eq!(days_matching("day 1", 2026, 8), vec![1]);
+        assert_eq!(days_matching("day 31", 2026, 9), Vec::<u32>::new());
+        assert_eq!(days_matching("last day", 2028, 2), vec![29]);
+        assert_eq!(
+            days_matching("every 2 weeks on tuesday from 2026-07-28", 2026, 8),
+            vec![11, 25]
+        );
+        assert_eq!(
+            days_matching("every 2 weeks on tuesday from 2026-08-10", 2026, 8),
+            vec![11, 25]
+        );
+        assert_eq!(
+            days_matching("every 1 week on tuesday from 2026-08-12", 2026, 8),
+            vec![18, 25]
+        );
+
+        for rule in [
+            "",
+            "every",
+            "day 32",
+            "sixth monday",
+            "last week",
+            "every 0 weeks on monday from 2026-08-03",
+        ] {
+            assert!(Recurrence::parse(rule).is_err(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let (tasks, diagnostics) = RecurringTasks::parse_csv_with_diagnostics(
+            "rule,priority,description\nday 1,1,Pagar la renta, luz y agua\nlast friday,6,Backup\nlast friday,9,Nada\nevery fryday,2,Nada\n\nday 2,3\nday 3,1, \n",
+        );
+        assert_eq!(tasks.0.len(), 2);
+        let found: Vec<(usize, usize, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.line, d.column, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, 13, "invalid priority '9', expected 1 to 6, skipped"),
+                (5, 1, "invalid recurrence 'every fryday', skipped"),
+                (
+                    7,
+                    1,
+                    "expected 3 fields (rule,priority,description), found 2, skipped"
+                ),
+                (8, 9, "empty description, skipped"),
+            ]
+        );
+        assert_eq!(tasks.0[0].description, "Pagar la renta, luz y agua");
+        assert_eq!(tasks.0[1].recurrence, Recurrence::LastWeekday(Weekday::Fri));
+    }
+
+    #[test]
+    fn test_insert_keeps_priority_order() {
+        let tasks = RecurringTasks::parse_csv(
+            "rule,priority,description\nday 1,1,Pagar la renta\nday 1,6,Backup\nday 2,3,Nada\n",
+        );
+        let content = "## 20260801 - Sábado\n\n- [ ] 1. Ejercicio\n- [ ] 2. Leer\n\n---\n";
+        assert_eq!(
+            tasks.insert(date(2026, 8, 1), content),
+            "## 20260801 - Sábado\n\n- [ ] 1. Ejercicio\n- [ ] 1. Pagar la renta\n- [ ] 2. Leer\n- [ ] 6. Backup\n\n---\n"
+        );
+        assert_eq!(
+            tasks.insert(date(2026, 8, 1), "## 20260801 - Sábado"),
+            "## 20260801 - Sábado\n- [ ] 1. Pagar la renta\n- [ ] 6. Backup\n"
+        );
+        assert_eq!(tasks.insert(date(2026, 8, 3), content), content);
+    }
+}
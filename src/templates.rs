@@ -0,0 +1,363 @@
+use crate::holidays::{HOLIDAY_TEMPLATE, Holiday};
+use crate::output::{Outcome, OverwriteMode, write_file};
+use crate::profiles::Profile;
+use chrono::{Datelike, NaiveDate};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tera::Tera;
+
+/// File name of the due-time rules inside a templates directory.
+pub const RULES_FILE: &str = "todos_due_times.csv";
+
+/// Templates compiled into the binary, used when no file on disk overrides them.
+pub const DEFAULT_TEMPLATES: [(&str, &str); 10] = [
+    ("header.md", include_str!("../templates/header.md")),
+    ("1.md", include_str!("../templates/1.md")),
+    ("2.md", include_str!("../templates/2.md")),
+    ("3.md", include_str!("../templates/3.md")),
+    ("4.md", include_str!("../templates/4.md")),
+    ("5.md", include_str!("../templates/5.md")),
+    ("6.md", include_str!("../templates/6.md")),
+    ("7.md", include_str!("../templates/7.md")),
+    (HOLIDAY_TEMPLATE, include_str!("../templates/holiday.md")),
+    (DAY_TEMPLATE, include_str!("../templates/day.md")),
+];
+
+/// Template used for every weekday without its own `N.md` file on disk, and for
+/// every day when rendering from a task catalogue.
+pub const DAY_TEMPLATE: &str = "day.md";
+
+/// Subdirectory of date-specific templates, `YYYY-MM-DD.md` or yearly `MM-DD.md`.
+pub const DATES_DIR: &str = "dates";
+
+fn is_weekday_template(name: &str) -> bool {
+    matches!(
+        name,
+        "1.md" | "2.md" | "3.md" | "4.md" | "5.md" | "6.md" | "7.md"
+    )
+}
+
+/// Markdown files directly inside `dir`, named with `prefix` plus their file name.
+fn markdown_files(dir: &Path, prefix: &str) -> io::Result<Vec<(PathBuf, Option<String>)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+            let name = path
+                .file_name()
+                .map(|name| format!("{}{}", prefix, name.to_string_lossy()));
+            files.push((path, name));
+        }
+    }
+    Ok(files)
+}
+
+/// Due-time rules compiled into the binary.
+pub const DEFAULT_RULES: &str = include_str!("../templates/todos_due_times.csv");
+
+/// Builds the template engine from the built-in templates, replacing or adding
+/// every `*.md` file found in `dir`, which must exist when given, and adding the
+/// date-specific templates of its `dates/` subdirectory.
+///
+/// A `day.md` file in `dir` replaces the built-in weekday templates, so only the
+/// weekdays with their own `N.md` file on disk differ from it.
+pub fn load_tera(dir: Option<&Path>) -> Result<Tera, Box<dyn std::error::Error>> {
+    let mut tera = Tera::default();
+    let has_day_template = dir.is_some_and(|dir| dir.join(DAY_TEMPLATE).is_file());
+    tera.add_raw_templates(
+        DEFAULT_TEMPLATES
+            .into_iter()
+            .filter(|(name, _)| !has_day_template || !is_weekday_template(name)),
+    )?;
+
+    if let Some(dir) = dir {
+        if !dir.is_dir() {
+            return Err(format!("templates directory not found: {}", dir.display()).into());
+        }
+        let mut files = markdown_files(dir, "")?;
+        let dates = dir.join(DATES_DIR);
+        if dates.is_dir() {
+            files.extend(markdown_files(&dates, &format!("{}/", DATES_DIR))?);
+        }
+        tera.add_template_files(files)?;
+    }
+
+    Ok(tera)
+}
+
+/// Name of the template rendering `date`, the first that applies of:
+/// `dates/YYYY-MM-DD.md`, `dates/MM-DD.md`, the template of the `profile` covering
+/// that date, the template of the `holiday` on that date, `N.md` for its weekday
+/// (1 for Monday to 7 for Sunday) unless `catalogue` is set, and `day.md`.
+pub fn day_template(
+    tera: &Tera,
+    date: NaiveDate,
+    holiday: Option<&Holiday>,
+    profile: Option<&Profile>,
+    catalogue: bool,
+) -> String {
+    let exists = |name: &str| tera.get_template_names().any(|template| template == name);
+    let dated = [
+        format!("{}/{}.md", DATES_DIR, date.format("%Y-%m-%d")),
+        format!("{}/{}.md", DATES_DIR, date.format("%m-%d")),
+    ];
+    if let Some(name) = dated.into_iter().find(|name| exists(name)) {
+        return name;
+    }
+    if let Some(template) = profile.and_then(|profile| profile.template.as_ref()) {
+        return template.clone();
+    }
+    if let Some(holiday) = holiday {
+        return holiday.template.clone();
+    }
+    let name = format!("{}.md", date.weekday().number_from_monday());
+    if !catalogue && exists(&name) {
+        name
+    } else {
+        DAY_TEMPLATE.to_string()
+    }
+}
+
+/// Reads the due-time rules CSV at `path`, or the built-in rules when there is none.
+pub fn load_rules(path: Option<&Path>) -> io::Result<String> {
+    match path {
+        Some(path) => fs::read_to_string(path),
+        None => Ok(DEFAULT_RULES.to_string()),
+    }
+}
+
+/// Writes the built-in templates and rules to `dir` so they can be edited.
+///
+/// Existing files are kept unless `overwrite` is `Force` or `Backup`; kept files are
+/// reported with `None`.
+pub fn init(dir: &Path, overwrite: OverwriteMode) -> io::Result<Vec<(PathBuf, Option<Outcome>)>> {
+    let files = DEFAULT_TEMPLATES
+        .iter()
+        .copied()
+        .chain(std::iter::once((RULES_FILE, DEFAULT_RULES)));
+
+    let mut written = Vec::new();
+    for (name, contents) in files {
+        let path = dir.join(name);
+        if path.exists() && overwrite == OverwriteMode::Refuse {
+            written.push((path, None));
+        } else {
+            let outcome = write_file(&path, contents, overwrite)?;
+            written.push((path, Some(outcome)));
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tera::Context;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("todos-templates-{}-{}", prefix, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_builtin_templates_render_without_files() {
+        let tera = load_tera(None).unwrap();
+        let mut context = Context::new();
+        context.insert("YYYYMM", "202608");
+        context.insert("PERIOD", "202608");
+        context.insert("YYYYMMDD", "20260803");
+        context.insert("weekday", "Lunes");
+        assert!(
+            tera.render("header.md", &context)
+                .unwrap()
+                .starts_with("# TODOS 202608")
+        );
+        assert!(
+            tera.render("1.md", &context)
+                .unwrap()
+                .starts_with("## 20260803 - Lunes")
+        );
+    }
+
+    #[test]
+    fn test_files_on_disk_override_builtin_templates() {
+        let dir = temp_dir("override");
+        fs::write(
+            dir.join("1.md"),
+            "## {{YYYYMMDD}} - Lunes\n\n- [ ] 1. Propia\n",
+        )
+        .unwrap();
+
+        let tera = load_tera(Some(&dir)).unwrap();
+        let mut context = Context::new();
+        context.insert("YYYYMMDD", "20260803");
+        context.insert("weekday", "Martes");
+        assert!(tera.render("1.md", &context).unwrap().contains("Propia"));
+        assert!(
+            tera.render("2.md", &context)
+                .unwrap()
+                .starts_with("## 20260803 - Martes")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_day_template_replaces_builtin_weekdays() {
+        let dir = temp_dir("day");
+        fs::write(
+            dir.join(DAY_TEMPLATE),
+            "## {{YYYYMMDD}} - {{ weekday }}\n\n{% if is_weekend %}- [ ] 1. Descansar{% else %}- [ ] 1. Trabajar{% endif %}\n",
+        )
+        .unwrap();
+        fs::write(dir.join("3.md"), "## {{YYYYMMDD}} - Miércoles\n").unwrap();
+
+        let tera = load_tera(Some(&dir)).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2026, 8, 3).unwrap();
+        let wednesday = NaiveDate::from_ymd_opt(2026, 8, 5).unwrap();
+        assert_eq!(day_template(&tera, monday, None, None, false), DAY_TEMPLATE);
+        assert_eq!(day_template(&tera, wednesday, None, None, false), "3.md");
+        let mut context = Context::new();
+        context.insert("YYYYMMDD", "20260808");
+        context.insert("weekday", "Sábado");
+        context.insert("is_weekend", &true);
+        assert!(
+            tera.render(DAY_TEMPLATE, &context)
+                .unwrap()
+                .contains("Descansar")
+        );
+        assert_eq!(
+            day_template(&load_tera(None).unwrap(), monday, None, None, false),
+            "1.md"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_date_templates_profiles_and_holidays_replace_weekday_templates() {
+        let dir = temp_dir("dates");
+        fs::create_dir_all(dir.join(DATES_DIR)).unwrap();
+        fs::write(dir.join(DATES_DIR).join("12-25.md"), "## Navidad\n").unwrap();
+        fs::write(
+            dir.join(DATES_DIR).join("2026-12-25.md"),
+            "## Navidad 2026\n",
+        )
+        .unwrap();
+
+        let tera = load_tera(Some(&dir)).unwrap();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            day_template(&tera, date(2026, 12, 25), None, None, false),
+            "dates/2026-12-25.md"
+        );
+        assert_eq!(
+            day_template(&tera, date(2027, 12, 25), None, None, false),
+            "dates/12-25.md"
+        );
+
+        let holiday = Holiday {
+            date: crate::holidays::HolidayDate::Yearly { month: 9, day: 16 },
+            name: "Independencia".to_string(),
+            template: HOLIDAY_TEMPLATE.to_string(),
+        };
+        let september = date(2026, 9, 16);
+        assert_eq!(
+            day_template(&tera, september, Some(&holiday), None, false),
+            HOLIDAY_TEMPLATE
+        );
+
+        let travel = Profile::parse("travel.md:2026-09-14:2026-12-31").unwrap();
+        assert_eq!(
+            day_template(&tera, september, Some(&holiday), Some(&travel), false),
+            "travel.md"
+        );
+        assert_eq!(
+            day_template(&tera, date(2026, 12, 25), None, Some(&travel), false),
+            "dates/2026-12-25.md"
+        );
+        let mut context = Context::new();
+        context.insert("YYYYMMDD", "20260916");
+        context.insert("weekday", "Miércoles");
+        context.insert("holiday", "Independencia");
+        let rendered = tera.render(HOLIDAY_TEMPLATE, &context).unwrap();
+        assert!(rendered.starts_with("## 20260916 - Miércoles\n\n**Independencia**\n"));
+        context.remove("holiday");
+        assert!(
+            !tera
+                .render(HOLIDAY_TEMPLATE, &context)
+                .unwrap()
+                .contains("**")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_catalogue_days_use_the_builtin_day_template() {
+        let tera = load_tera(None).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2026, 8, 3).unwrap();
+        assert_eq!(day_template(&tera, monday, None, None, true), DAY_TEMPLATE);
+
+        let mut context = Context::new();
+        context.insert("YYYYMMDD", "20260803");
+        context.insert("weekday", "Lunes");
+        assert_eq!(
+            tera.render(DAY_TEMPLATE, &context).unwrap(),
+            "## 20260803 - Lunes\n\n"
+        );
+        #[derive(serde::Serialize)]
+        struct Task {
+            priority: u8,
+            description: &'static str,
+        }
+        let tasks = [
+            Task {
+                priority: 1,
+                description: "[[Ejercicio]]",
+            },
+            Task {
+                priority: 2,
+                description: "Trabajar",
+            },
+        ];
+        context.insert("tasks", &tasks);
+        assert_eq!(
+            tera.render(DAY_TEMPLATE, &context).unwrap(),
+            "## 20260803 - Lunes\n\n- [ ] 1. [[Ejercicio]]\n- [ ] 2. Trabajar\n"
+        );
+    }
+
+    #[test]
+    fn test_builtin_rules_parse() {
+        let rules = crate::parser::CsvParser::parse(&load_rules(None).unwrap());
+        assert_eq!(rules.len(), 42);
+    }
+
+    #[test]
+    fn test_init_keeps_existing_files() {
+        let dir = temp_dir("init");
+        fs::write(dir.join("1.md"), "propia").unwrap();
+
+        let written = init(&dir, OverwriteMode::Refuse).unwrap();
+        assert_eq!(written.len(), 11);
+        let kept: Vec<_> = written.iter().filter(|(_, o)| o.is_none()).collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(fs::read_to_string(dir.join("1.md")).unwrap(), "propia");
+        assert_eq!(
+            fs::read_to_string(dir.join(RULES_FILE)).unwrap(),
+            DEFAULT_RULES
+        );
+
+        init(&dir, OverwriteMode::Force).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("1.md")).unwrap(),
+            DEFAULT_TEMPLATES[1].1
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
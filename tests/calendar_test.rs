@@ -1,10 +1,12 @@
-use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
-use todos_cli::calendar::{generate_ics, generate_uid};
-use todos_cli::parser::{DueTimeRule, TodoItem};
+use chrono::{Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use todos_cli::calendar::{
+    IcsOptions, ProfileRules, generate_ics, generate_ics_with, generate_uid,
+};
+use todos_cli::parser::{DueTimeRule, MdParser, TodoItem, TodoStatus};
 
-fn make_rule(weekday: &str, priority: u8, hour: &str, alarm_minutes: u16) -> DueTimeRule {
+fn make_rule(weekday: Weekday, priority: u8, hour: &str, alarm_minutes: u16) -> DueTimeRule {
     DueTimeRule {
-        weekday: weekday.to_string(),
+        weekday,
         priority,
         hour: NaiveTime::parse_from_str(hour, "%H:%M").unwrap(),
         alarm_minutes,
@@ -29,6 +31,10 @@ fn test_ics_basic_structure() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: "Ejercicio".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
     let ics = generate_ics("TODOS - 202607", &items, &[]);
@@ -71,12 +77,20 @@ fn test_ics_vevent_count() {
             weekday_name: "Miércoles".to_string(),
             priority: 1,
             description: "Task 1".to_string(),
+            status: TodoStatus::Open,
+            start: None,
+            end: None,
+            duration: None,
         },
         TodoItem {
             date: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
             weekday_name: "Miércoles".to_string(),
             priority: 2,
             description: "Task 2".to_string(),
+            status: TodoStatus::Open,
+            start: None,
+            end: None,
+            duration: None,
         },
     ];
 
@@ -110,9 +124,13 @@ fn test_event_timestamp_from_csv_match() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: "Ejercicio".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
-    let rules = vec![make_rule("Miércoles", 1, "09:00", 30)];
+    let rules = vec![make_rule(Weekday::Wed, 1, "09:00", 30)];
     let ics = generate_ics("TODOS - 202607", &items, &rules);
 
     let expected_dtstart = local_utc(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(), 9, 0, 0);
@@ -138,9 +156,13 @@ fn test_event_timestamp_no_csv_match() {
         weekday_name: "Miércoles".to_string(),
         priority: 7,
         description: "No match".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
-    let rules = vec![make_rule("Miércoles", 1, "09:00", 30)];
+    let rules = vec![make_rule(Weekday::Wed, 1, "09:00", 30)];
     let ics = generate_ics("TODOS - 202607", &items, &rules);
 
     let expected_dtstart = local_utc(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(), 9, 0, 0);
@@ -159,6 +181,44 @@ fn test_event_timestamp_no_csv_match() {
     );
 }
 
+#[test]
+fn test_profile_rules_replace_rules_on_their_days() {
+    let item = |day: u32| TodoItem {
+        date: NaiveDate::from_ymd_opt(2026, 8, day).unwrap(),
+        weekday_name: String::new(),
+        priority: 1,
+        description: "Ejercicio".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
+    };
+    let items = vec![item(4), item(11)];
+    let rules = vec![make_rule(Weekday::Tue, 1, "07:00", 30)];
+    let options = IcsOptions {
+        profile_rules: vec![ProfileRules {
+            from: NaiveDate::from_ymd_opt(2026, 8, 3).unwrap(),
+            to: NaiveDate::from_ymd_opt(2026, 8, 9).unwrap(),
+            rules: vec![],
+        }],
+        ..IcsOptions::default()
+    };
+    let ics = generate_ics_with("TODOS - 202608", &items, &rules, &options);
+
+    let travel_start = local_utc(NaiveDate::from_ymd_opt(2026, 8, 4).unwrap(), 9, 0, 0);
+    let work_start = local_utc(NaiveDate::from_ymd_opt(2026, 8, 11).unwrap(), 7, 0, 0);
+    assert!(
+        ics.contains(&format!("DTSTART:{}\r\n", travel_start)),
+        "Profile days without rules should use the default start"
+    );
+    assert!(ics.contains(&format!("DTSTART:{}\r\n", work_start)));
+    assert_eq!(
+        ics.matches("BEGIN:VALARM").count(),
+        1,
+        "Only the day outside the profile should have a reminder"
+    );
+}
+
 #[test]
 fn test_line_folding_max_75_octets() {
     let long_desc = "A".repeat(95);
@@ -167,6 +227,10 @@ fn test_line_folding_max_75_octets() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: long_desc,
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
     let ics = generate_ics("TODOS - 202607", &items, &[]);
@@ -190,6 +254,10 @@ fn test_content_escaping() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: "Escape \\ ; comma , and\nnewline".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
     let ics = generate_ics("TODOS - 202607", &items, &[]);
@@ -206,6 +274,10 @@ fn test_uid_format() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: "Task".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
     let ics = generate_ics("TODOS - 202607", &items, &[]);
@@ -220,6 +292,10 @@ fn test_dtstamp_format() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: "Task".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
     let ics = generate_ics("TODOS - 202607", &items, &[]);
@@ -237,12 +313,115 @@ fn test_crlf_line_endings() {
         weekday_name: "Miércoles".to_string(),
         priority: 1,
         description: "Task".to_string(),
+        status: TodoStatus::Open,
+        start: None,
+        end: None,
+        duration: None,
     }];
 
     let ics = generate_ics("TODOS - 202607", &items, &[]);
     assert!(ics.contains("\r\n"), "Should use CRLF line endings");
 }
 
+#[test]
+fn test_inline_times_override_the_rules() {
+    let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+    let item = |priority: u8, start: &str, end: Option<&str>| TodoItem {
+        date,
+        weekday_name: "Miércoles".to_string(),
+        priority,
+        description: format!("Task {}", priority),
+        status: TodoStatus::Open,
+        start: Some(NaiveTime::parse_from_str(start, "%H:%M").unwrap()),
+        end: end.map(|end| NaiveTime::parse_from_str(end, "%H:%M").unwrap()),
+        duration: None,
+    };
+    let items = vec![
+        item(1, "09:00", Some("17:00")),
+        item(2, "14:30", None),
+        item(3, "22:00", Some("01:00")),
+        item(4, "11:00", Some("11:00")),
+    ];
+    let rules = vec![make_rule(Weekday::Wed, 1, "06:00", 30)];
+    let ics = generate_ics("TODOS - 202607", &items, &rules);
+
+    let next_day = date.succ_opt().unwrap();
+    for (start, end) in [
+        (local_utc(date, 9, 0, 0), local_utc(date, 17, 0, 0)),
+        (local_utc(date, 14, 30, 0), local_utc(date, 15, 30, 0)),
+        (local_utc(date, 22, 0, 0), local_utc(next_day, 1, 0, 0)),
+        // An empty range lasts the default hour rather than a whole day.
+        (local_utc(date, 11, 0, 0), local_utc(date, 12, 0, 0)),
+    ] {
+        assert!(ics.contains(&format!("DTSTART:{}\r\nDTEND:{}\r\n", start, end)));
+    }
+    assert!(ics.contains("TRIGGER:-PT30M"), "The rule's alarm is kept");
+}
+
+#[test]
+fn test_inline_durations_set_the_event_end() {
+    let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+    let item = |priority: u8, end: Option<u32>, minutes: Option<i64>| TodoItem {
+        date,
+        weekday_name: "Miércoles".to_string(),
+        priority,
+        description: format!("Task {}", priority),
+        status: TodoStatus::Open,
+        start: None,
+        end: end.and_then(|hour| NaiveTime::from_hms_opt(hour, 0, 0)),
+        duration: minutes.map(Duration::minutes),
+    };
+    let items = vec![
+        item(1, None, Some(120)),
+        item(2, Some(18), Some(30)),
+        // Past the last representable date, so the default duration is used.
+        item(3, None, Some(i64::from(u32::MAX) * 60)),
+    ];
+    let rules = vec![make_rule(Weekday::Wed, 2, "16:00", 0)];
+    let options = IcsOptions {
+        default_duration: Duration::minutes(15),
+        ..IcsOptions::default()
+    };
+    let ics = generate_ics_with("TODOS - 202607", &items, &rules, &options);
+
+    for (start, end) in [
+        (local_utc(date, 9, 0, 0), local_utc(date, 11, 0, 0)),
+        (local_utc(date, 16, 0, 0), local_utc(date, 18, 0, 0)),
+        (local_utc(date, 9, 0, 0), local_utc(date, 9, 15, 0)),
+    ] {
+        assert!(ics.contains(&format!("DTSTART:{}\r\nDTEND:{}\r\n", start, end)));
+    }
+}
+
+#[test]
+fn test_done_and_deferred_items_are_left_out_and_cancelled_ones_flagged() {
+    let item = |description: &str, status: TodoStatus| TodoItem {
+        date: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
+        weekday_name: "Miércoles".to_string(),
+        priority: 1,
+        description: description.to_string(),
+        status,
+        start: None,
+        end: None,
+        duration: None,
+    };
+    let items = vec![
+        item("Open", TodoStatus::Open),
+        item("Done", TodoStatus::Done),
+        item("Cancelled", TodoStatus::Cancelled),
+        item("Deferred", TodoStatus::Deferred),
+    ];
+    let rules = vec![make_rule(Weekday::Wed, 1, "08:00", 15)];
+
+    let ics = generate_ics("TODOS - 202607", &items, &rules);
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(!ics.contains("SUMMARY:[P1] Done"));
+    assert!(!ics.contains("SUMMARY:[P1] Deferred"));
+    assert!(ics.contains("SUMMARY:[P1] Cancelled\r\nSTATUS:CANCELLED\r\nEND:VEVENT"));
+    assert_eq!(ics.matches("STATUS:").count(), 1);
+    assert_eq!(ics.matches("BEGIN:VALARM").count(), 1);
+}
+
 #[test]
 fn test_generate_uid_uniqueness() {
     let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
@@ -258,3 +437,20 @@ fn test_generate_uid_uniqueness() {
         "Different priorities should produce different UIDs"
     );
 }
+
+#[test]
+fn test_rules_follow_the_date_not_the_heading() {
+    // 2026-08-01 is a Saturday copied from a Monday block.
+    let md = "## 20260801 - Lunes\n- [ ] 1. Ejercicio\n## 20260803\n- [ ] 1. Leer\n";
+    let items = MdParser::parse(md);
+    let rules = vec![
+        make_rule(Weekday::Mon, 1, "06:00", 30),
+        make_rule(Weekday::Sat, 1, "10:00", 0),
+    ];
+    let ics = generate_ics("TODOS - 202608", &items, &rules);
+
+    let saturday = NaiveDate::from_ymd_opt(2026, 8, 1).unwrap();
+    let monday = NaiveDate::from_ymd_opt(2026, 8, 3).unwrap();
+    assert!(ics.contains(&format!("DTSTART:{}\r\n", local_utc(saturday, 10, 0, 0))));
+    assert!(ics.contains(&format!("DTSTART:{}\r\n", local_utc(monday, 6, 0, 0))));
+}
@@ -3,6 +3,16 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+const ENGLISH_WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
 const SPANISH_WEEKDAYS: [&str; 7] = [
     "Lunes",
     "Martes",
@@ -13,9 +23,41 @@ const SPANISH_WEEKDAYS: [&str; 7] = [
     "Domingo",
 ];
 
+/// CLI command isolated from the developer's own templates and config.
+fn cli() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_todos-cli"));
+    command
+        .env_remove("TODOS_TEMPLATES_DIR")
+        .env_remove("TODOS_RULES")
+        .env_remove("TODOS_CALENDAR_NAME")
+        .env_remove("TODOS_EVENT_DURATION")
+        .env_remove("TODOS_START_TIME")
+        .env_remove("TODOS_LOCALE")
+        .env_remove("TODOS_LAYOUT")
+        .env_remove("TODOS_DAILY_FOLDER")
+        .env_remove("TODOS_DAILY_PATTERN")
+        .env_remove("TODOS_FRONTMATTER")
+        .env_remove("TODOS_TAGS")
+        .env_remove("TODOS_HOLIDAYS")
+        .env_remove("TODOS_PROFILES")
+        .env_remove("TODOS_RECURRING")
+        .env_remove("TODOS_TASKS")
+        .env_remove("TODOS_STRICT")
+        .env(
+            "XDG_CONFIG_HOME",
+            std::env::temp_dir().join("todos-e2e-no-config"),
+        )
+        .env(
+            "XDG_STATE_HOME",
+            std::env::temp_dir().join("todos-e2e-state"),
+        );
+    command
+}
+
 fn run_cli(year: i32, month: u32, out_dir: &Path) -> std::process::Output {
-    Command::new(env!("CARGO_BIN_EXE_todos-cli"))
+    cli()
         .args([
+            "new",
             "--year",
             &year.to_string(),
             "--month",
@@ -27,6 +69,10 @@ fn run_cli(year: i32, month: u32, out_dir: &Path) -> std::process::Output {
         .expect("Failed to run CLI")
 }
 
+fn run_cli_args(args: &[&str]) -> std::process::Output {
+    cli().args(args).output().expect("Failed to run CLI")
+}
+
 fn temp_dir(prefix: &str) -> PathBuf {
     let nanos = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -378,3 +424,816 @@ fn test_temp_dir_preserved_on_failure() {
     );
     fs::remove_dir_all(&dir).unwrap();
 }
+
+#[test]
+fn test_ics_subcommand_regenerates_from_edited_markdown() {
+    let dir = temp_dir("regenerate-ics");
+    let output = run_cli(2026, 7, &dir);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md_path = dir.join("TODOS - 202607.md");
+    let edited = fs::read_to_string(&md_path).unwrap().replacen(
+        "- [ ] 1. [[Ejercicio]]",
+        "- [ ] 1. Nadar 1 km",
+        1,
+    ) + "- [ ] 6. Tarea agregada\n";
+    fs::write(&md_path, &edited).unwrap();
+
+    let output = run_cli_args(&["ics", "--file", md_path.to_str().unwrap()]);
+    assert!(output.status.success(), "ics subcommand should succeed");
+
+    let ics = fs::read_to_string(dir.join("TODOS - 202607.ics")).unwrap();
+    assert!(ics.contains("SUMMARY:[P1] Nadar 1 km"));
+    assert!(ics.contains("SUMMARY:[P6] Tarea agregada"));
+    assert_eq!(
+        count_occurrences(&ics, "BEGIN:VEVENT"),
+        count_occurrences(&edited, "- [ ] "),
+        "VEVENT count should follow the edited markdown"
+    );
+    assert_eq!(
+        fs::read_to_string(&md_path).unwrap(),
+        edited,
+        "Markdown must be left untouched"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_checked_items_leave_the_calendar_and_show_in_stats() {
+    let dir = temp_dir("checkbox-states");
+    let output = run_cli(2026, 7, &dir);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md_path = dir.join("TODOS - 202607.md");
+    let md = fs::read_to_string(&md_path).unwrap();
+    let open = count_occurrences(&md, "- [ ] ");
+    let edited = md
+        .replacen("- [ ] 1. [[Ejercicio]]", "- [x] 1. [[Ejercicio]]", 2)
+        .replacen("- [ ] 1. [[Ejercicio]]", "- [-] 1. [[Ejercicio]]", 1)
+        .replacen("- [ ] 1. [[Ejercicio]]", "- [>] 1. [[Ejercicio]]", 1);
+    fs::write(&md_path, &edited).unwrap();
+
+    let md_arg = md_path.to_str().unwrap();
+    let output = run_cli_args(&["ics", "--file", md_arg]);
+    assert!(output.status.success(), "ics subcommand should succeed");
+    let ics = fs::read_to_string(dir.join("TODOS - 202607.ics")).unwrap();
+    assert_eq!(count_occurrences(&ics, "BEGIN:VEVENT"), open - 3);
+    assert_eq!(count_occurrences(&ics, "STATUS:CANCELLED"), 1);
+
+    let output = run_cli_args(&["list", "--file", md_arg]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("20260701 Miércoles [x] [P1] Ejercicio"));
+    assert!(stdout.contains("20260703 Viernes [-] [P1] Ejercicio"));
+
+    let output = run_cli_args(&["stats", "--file", md_arg]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Hechas: 2, canceladas: 1, pospuestas: 1"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_malformed_lines_are_reported_and_fail_under_strict() {
+    let dir = temp_dir("diagnostics");
+    let output = run_cli(2026, 7, &dir);
+    assert!(output.status.success(), "CLI should exit successfully");
+    assert!(
+        output.stderr.is_empty(),
+        "Generated files should have no problems"
+    );
+
+    let md_path = dir.join("TODOS - 202607.md");
+    let md = fs::read_to_string(&md_path).unwrap();
+    let lines = md.lines().count();
+    fs::write(&md_path, md + "- [ ] 9. Tarea agregada\n").unwrap();
+
+    let md_arg = md_path.to_str().unwrap();
+    let output = run_cli_args(&["list", "--file", md_arg]);
+    assert!(output.status.success(), "Warnings alone should not fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: priority 9 outside 1 to 6, using 6"));
+    assert!(stderr.contains(&format!("--> {}:{}:7", md_arg, lines + 1)));
+    assert!(stderr.contains("| - [ ] 9. Tarea agregada\n"));
+
+    let output = run_cli_args(&["list", "--file", md_arg, "--strict"]);
+    assert!(!output.status.success(), "--strict should fail");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_lint_checks_and_fixes_ivy_lee_rules() {
+    let dir = temp_dir("lint");
+    let dir_arg = dir.to_str().unwrap();
+    let output = run_cli(2026, 8, &dir);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let output = run_cli_args(&["lint", "-y", "2026", "-m", "8", "-p", dir_arg]);
+    assert!(output.status.success(), "Generated files follow the rules");
+
+    let md_path = dir.join("TODOS - 202608.md");
+    let md = fs::read_to_string(&md_path).unwrap();
+    let start = md.find("## 20260805").unwrap();
+    let end = md.find("## 20260806").unwrap();
+    let edited = format!("{}{}", &md[..start], &md[end..])
+        .replace("## 20260801 - Sábado", "## 20260801 - Lunes")
+        .replacen("- [ ] 3. Limpiar la casa", "- [ ] 6. Limpiar la casa", 1);
+    fs::write(&md_path, &edited).unwrap();
+
+    let output = run_cli_args(&["lint", "-y", "2026", "-m", "8", "-p", dir_arg]);
+    assert!(!output.status.success(), "Lint should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: 'Lunes' is not the weekday of 20260801"));
+    assert!(stderr.contains("warning: priority 3 is skipped in 20260802"));
+    assert!(stderr.contains("warning: missing day 20260805"));
+    assert_eq!(fs::read_to_string(&md_path).unwrap(), edited);
+
+    let md_arg = md_path.to_str().unwrap();
+    let output = run_cli_args(&["lint", "--file", md_arg, "--fix"]);
+    assert!(!output.status.success(), "The missing day is left to fix");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Problemas corregidos: 2"));
+    assert!(stdout.contains("TODOS - 202608: 1 problemas"));
+    let fixed = fs::read_to_string(&md_path).unwrap();
+    assert!(fixed.contains("## 20260801 - Sábado"));
+    assert!(fixed.contains("- [ ] 5. Limpiar la casa\n- [ ] 3. Descansar\n"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_ics_subcommand_fails_without_markdown() {
+    let dir = temp_dir("regenerate-missing");
+    let output = run_cli_args(&["ics", "-y", "2026", "-m", "7", "-p", dir.to_str().unwrap()]);
+    assert!(!output.status.success(), "Missing markdown should fail");
+    assert!(!dir.join("TODOS - 202607.ics").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_new_refuses_to_overwrite_existing_month() {
+    let dir = temp_dir("refuse-overwrite");
+    let output = run_cli(2026, 7, &dir);
+    assert!(output.status.success(), "First run should succeed");
+
+    let md_path = dir.join("TODOS - 202607.md");
+    fs::write(&md_path, "notas del mes").unwrap();
+
+    let output = run_cli(2026, 7, &dir);
+    assert!(!output.status.success(), "Second run should be refused");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--force"), "Error should mention --force");
+    assert_eq!(fs::read_to_string(&md_path).unwrap(), "notas del mes");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_new_backup_keeps_timestamped_copies() {
+    let dir = temp_dir("backup-overwrite");
+    let output = run_cli(2026, 7, &dir);
+    assert!(output.status.success(), "First run should succeed");
+
+    let md_path = dir.join("TODOS - 202607.md");
+    fs::write(&md_path, "notas del mes").unwrap();
+
+    let dir_arg = dir.to_str().unwrap();
+    let output = run_cli_args(&["new", "-y", "2026", "-m", "7", "-p", dir_arg, "--backup"]);
+    assert!(output.status.success(), "Backup run should succeed");
+
+    let backups: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".bak"))
+        .collect();
+    assert_eq!(backups.len(), 2, "Both MD and ICS should be backed up");
+    let md_backup = backups
+        .iter()
+        .find(|name| name.starts_with("TODOS - 202607.md."))
+        .expect("MD backup should exist");
+    assert_eq!(
+        fs::read_to_string(dir.join(md_backup)).unwrap(),
+        "notas del mes"
+    );
+    assert!(
+        fs::read_to_string(&md_path)
+            .unwrap()
+            .starts_with("# TODOS 202607")
+    );
+
+    let output = run_cli_args(&["new", "-y", "2026", "-m", "7", "-p", dir_arg, "--force"]);
+    assert!(output.status.success(), "Forced run should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("reemplazado"),
+        "Replacement should be reported"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_builtin_templates_work_outside_the_repo() {
+    let dir = temp_dir("outside-repo");
+    let output = cli()
+        .args(["new", "-y", "2026", "-m", "7", "-p", "."])
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success(), "CLI should not need templates/");
+
+    let md = fs::read_to_string(dir.join("TODOS - 202607.md")).unwrap();
+    assert_eq!(day_headings(&md).len(), 31);
+    let ics = fs::read_to_string(dir.join("TODOS - 202607.ics")).unwrap();
+    assert_eq!(
+        count_occurrences(&ics, "BEGIN:VALARM"),
+        count_occurrences(&md, "- [ ] "),
+        "Built-in due-time rules should give every event an alarm"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_templates_init_writes_editable_overrides() {
+    let dir = temp_dir("templates-init");
+    let run_in_dir = |args: &[&str]| {
+        cli()
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("Failed to run CLI")
+    };
+
+    let output = run_in_dir(&["templates", "init"]);
+    assert!(output.status.success(), "templates init should succeed");
+    for name in ["header.md", "1.md", "7.md", "todos_due_times.csv"] {
+        assert!(
+            dir.join("templates").join(name).exists(),
+            "{} missing",
+            name
+        );
+    }
+
+    fs::write(
+        dir.join("templates").join("header.md"),
+        "# Mis TODOS {{ YYYYMM }}\n\n",
+    )
+    .unwrap();
+    let output = run_in_dir(&["new", "-y", "2026", "-m", "7", "-p", "."]);
+    assert!(output.status.success(), "CLI should exit successfully");
+    let md = fs::read_to_string(dir.join("TODOS - 202607.md")).unwrap();
+    assert!(md.starts_with("# Mis TODOS 202607"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_templates_and_rules_from_xdg_config_dir() {
+    let dir = temp_dir("xdg-config");
+    let config = dir.join("config").join("todos-cli");
+    let templates = config.join("templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(templates.join("header.md"), "# Equipo {{ YYYYMM }}\n\n").unwrap();
+    fs::write(
+        config.join("todos_due_times.csv"),
+        "weekday,priority,hour,minutes\nMiércoles,1,7:00,45\n",
+    )
+    .unwrap();
+
+    let output = cli()
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .args(["new", "-y", "2026", "-m", "7", "-p", "out"])
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md = fs::read_to_string(dir.join("out").join("TODOS - 202607.md")).unwrap();
+    assert!(md.starts_with("# Equipo 202607"));
+    let ics = fs::read_to_string(dir.join("out").join("TODOS - 202607.ics")).unwrap();
+    assert!(ics.contains("TRIGGER:-PT45M"));
+    assert!(!ics.contains("TRIGGER:-PT30M"));
+
+    let flag_templates = dir.join("flag");
+    fs::create_dir_all(&flag_templates).unwrap();
+    fs::write(flag_templates.join("header.md"), "# Flag {{ YYYYMM }}\n\n").unwrap();
+    let output = cli()
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .args(["new", "-y", "2026", "-m", "8", "-p", "out"])
+        .arg("--templates-dir")
+        .arg(&flag_templates)
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success(), "CLI should exit successfully");
+    let md = fs::read_to_string(dir.join("out").join("TODOS - 202608.md")).unwrap();
+    assert!(md.starts_with("# Flag 202608"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_config_files_and_config_show() {
+    let dir = temp_dir("config-file");
+    let config = dir.join("config").join("todos-cli");
+    fs::create_dir_all(&config).unwrap();
+    fs::write(
+        config.join("config.toml"),
+        "path = \"vault\"\ncalendar_name = \"Mis TODOS\"\nevent_duration = 30\n",
+    )
+    .unwrap();
+    fs::write(dir.join(".todos-cli.toml"), "event_duration = 45\n").unwrap();
+
+    let run_in_dir = |args: &[&str]| {
+        cli()
+            .env("XDG_CONFIG_HOME", dir.join("config"))
+            .env_remove("TODOS_DEFAULT_PATH")
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("Failed to run CLI")
+    };
+
+    let output = run_in_dir(&["new", "-y", "2026", "-m", "7"]);
+    assert!(output.status.success(), "CLI should exit successfully");
+    let ics = fs::read_to_string(config.join("vault").join("TODOS - 202607.ics")).unwrap();
+    assert!(ics.contains("X-WR-CALNAME:Mis TODOS\r\n"));
+
+    let output = run_in_dir(&["config", "show", "--start-time", "07:00"]);
+    assert!(output.status.success(), "config show should succeed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("calendar_name = \"Mis TODOS\"  # config file"));
+    assert!(stdout.contains("event_duration = 45  # config file ./.todos-cli.toml"));
+    assert!(stdout.contains("start_time = \"07:00\"  # flag --start-time"));
+    assert!(stdout.contains("rules = (built-in)  # built-in"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_english_locale_names_weekdays_and_messages() {
+    let dir = temp_dir("locale-en");
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "7",
+        "-p",
+        dir.to_str().unwrap(),
+        "--locale",
+        "en",
+    ]);
+    assert!(output.status.success(), "CLI should exit successfully");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("TODOS file created: "));
+    assert!(stdout.contains("ICS file created: "));
+
+    let md = fs::read_to_string(dir.join("TODOS - 202607.md")).unwrap();
+    for (i, heading) in day_headings(&md).iter().enumerate() {
+        let date = NaiveDate::from_ymd_opt(2026, 7, i as u32 + 1).unwrap();
+        let expected = ENGLISH_WEEKDAYS[date.weekday().num_days_from_monday() as usize];
+        assert_eq!(
+            heading,
+            &format!("{} - {}", date.format("%Y%m%d"), expected)
+        );
+    }
+
+    let ics = fs::read_to_string(dir.join("TODOS - 202607.ics")).unwrap();
+    assert_eq!(
+        count_occurrences(&ics, "BEGIN:VALARM"),
+        count_occurrences(&md, "- [ ] "),
+        "Spanish due-time rules should apply to English weekday names"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_new_generates_a_range_of_months_and_combined_ics() {
+    let dir = temp_dir("month-range");
+    let dir_arg = dir.to_str().unwrap();
+    let output = run_cli_args(&[
+        "new",
+        "--from",
+        "2026-11",
+        "--to",
+        "2027-02",
+        "--combined-ics",
+        "-p",
+        dir_arg,
+    ]);
+    assert!(output.status.success(), "Range run should succeed");
+
+    let mut events = 0;
+    for stem in [
+        "TODOS - 202611",
+        "TODOS - 202612",
+        "TODOS - 202701",
+        "TODOS - 202702",
+    ] {
+        assert!(dir.join(format!("{}.md", stem)).exists(), "{} md", stem);
+        let ics = fs::read_to_string(dir.join(format!("{}.ics", stem))).unwrap();
+        events += count_occurrences(&ics, "BEGIN:VEVENT");
+    }
+    let combined = fs::read_to_string(dir.join("TODOS - 202611-202702.ics")).unwrap();
+    assert_eq!(count_occurrences(&combined, "BEGIN:VCALENDAR"), 1);
+    assert_eq!(count_occurrences(&combined, "BEGIN:VEVENT"), events);
+    assert!(combined.contains("X-WR-CALNAME:TODOS - 202611-202702"));
+
+    let output = run_cli_args(&["new", "--from", "2027-02", "--to", "2027-03", "-p", dir_arg]);
+    assert!(
+        !output.status.success(),
+        "Overlapping range should be refused"
+    );
+    assert!(
+        !dir.join("TODOS - 202703.md").exists(),
+        "Nothing should be written when a month already exists"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_new_year_only_generates_twelve_months() {
+    let dir = temp_dir("year-only");
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2027",
+        "--year-only",
+        "-p",
+        dir.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "Year run should succeed");
+
+    for month in 1..=12 {
+        let md = fs::read_to_string(dir.join(format!("TODOS - 2027{:02}.md", month))).unwrap();
+        assert_eq!(day_headings(&md).len(), expected_days(2027, month).len());
+        assert!(dir.join(format!("TODOS - 2027{:02}.ics", month)).exists());
+    }
+    assert!(!dir.join("TODOS - 202701-202712.ics").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_new_week_spans_a_month_boundary() {
+    let dir = temp_dir("iso-week");
+    let dir_arg = dir.to_str().unwrap();
+    let output = run_cli_args(&["new", "-y", "2026", "-w", "31", "-p", dir_arg]);
+    assert!(output.status.success(), "Week run should succeed");
+
+    let md = fs::read_to_string(dir.join("TODOS - 2026-W31.md")).unwrap();
+    assert!(md.starts_with("# TODOS 2026-W31\n"));
+    assert_eq!(
+        day_headings(&md),
+        vec![
+            "20260727 - Lunes",
+            "20260728 - Martes",
+            "20260729 - Miércoles",
+            "20260730 - Jueves",
+            "20260731 - Viernes",
+            "20260801 - Sábado",
+            "20260802 - Domingo",
+        ]
+    );
+    let ics = fs::read_to_string(dir.join("TODOS - 2026-W31.ics")).unwrap();
+    assert_eq!(
+        count_occurrences(&ics, "BEGIN:VEVENT"),
+        count_occurrences(&md, "- [ ] ")
+    );
+
+    let output = run_cli_args(&["list", "-y", "2026", "-w", "31", "-p", dir_arg]);
+    assert!(output.status.success(), "Listing the week should succeed");
+
+    let output = run_cli_args(&["new", "-y", "2027", "-w", "53", "-p", dir_arg]);
+    assert!(!output.status.success(), "2027 has no week 53");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_daily_pattern_sharing_notes_writes_nothing() {
+    for (pattern, period) in [
+        ("%A", ["-y", "2026", "-m", "8"]),
+        ("%d", ["--from", "2026-08", "--to", "2026-09"]),
+        // Valid for a year, but 20261201 and 20271201 share a note.
+        ("%m-%d", ["--from", "2026-12", "--to", "2027-12"]),
+    ] {
+        let dir = temp_dir("daily-collision");
+        let mut args = vec!["new", "-p", dir.to_str().unwrap(), "--force"];
+        args.extend(period);
+        args.extend(["--layout", "daily", "--daily-pattern", pattern]);
+        let output = run_cli_args(&args);
+        assert!(!output.status.success(), "{} should be rejected", pattern);
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            0,
+            "{} should write no file",
+            pattern
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[test]
+fn test_daily_notes_layout_with_index_note() {
+    let dir = temp_dir("daily-notes");
+    let dir_arg = dir.to_str().unwrap();
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "8",
+        "-p",
+        dir_arg,
+        "--layout",
+        "daily",
+        "--daily-folder",
+        "Diario",
+    ]);
+    assert!(output.status.success(), "Daily notes run should succeed");
+
+    let notes: Vec<_> = fs::read_dir(dir.join("Diario")).unwrap().collect();
+    assert_eq!(notes.len(), 31);
+    let note = fs::read_to_string(dir.join("Diario").join("2026-08-15.md")).unwrap();
+    assert!(note.starts_with("## 20260815 - Sábado"));
+    let index = fs::read_to_string(dir.join("TODOS - 202608.md")).unwrap();
+    assert!(index.contains("- [[Diario/2026-08-01|20260801 - Sábado]]"));
+
+    let planned: usize = (1..=31)
+        .map(|day| {
+            let note = dir.join("Diario").join(format!("2026-08-{:02}.md", day));
+            count_occurrences(&fs::read_to_string(note).unwrap(), "- [ ] ")
+        })
+        .sum();
+    let ics = fs::read_to_string(dir.join("TODOS - 202608.ics")).unwrap();
+    assert_eq!(count_occurrences(&ics, "BEGIN:VEVENT"), planned);
+
+    let output = run_cli_args(&["ics", "-f", dir.join("TODOS - 202608.md").to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "ICS should be regenerated from the notes"
+    );
+    let ics = fs::read_to_string(dir.join("TODOS - 202608.ics")).unwrap();
+    assert_eq!(count_occurrences(&ics, "BEGIN:VEVENT"), planned);
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "8",
+        "-p",
+        dir_arg,
+        "--layout",
+        "daily",
+        "--daily-folder",
+        "Diario",
+    ]);
+    assert!(!output.status.success(), "Existing notes should be kept");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_date_templates_and_holidays_list() {
+    let dir = temp_dir("holidays");
+    let templates = dir.join("templates");
+    fs::create_dir_all(templates.join("dates")).unwrap();
+    fs::write(
+        templates.join("dates").join("12-24.md"),
+        "## {{YYYYMMDD}} - {{ weekday }}\n\n- [ ] 1. Cena de Nochebuena\n",
+    )
+    .unwrap();
+    fs::write(
+        templates.join("holidays.csv"),
+        "date,name,template\n12-25,Navidad,\n2026-12-31,Fin de año,fiesta.md\n",
+    )
+    .unwrap();
+    fs::write(
+        templates.join("fiesta.md"),
+        "## {{YYYYMMDD}} - {{ weekday }}\n\n- [ ] 1. {{ holiday }}\n",
+    )
+    .unwrap();
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "12",
+        "-p",
+        dir.to_str().unwrap(),
+        "--templates-dir",
+        templates.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md = fs::read_to_string(dir.join("TODOS - 202612.md")).unwrap();
+    assert!(md.contains("## 20261224 - Jueves\n\n- [ ] 1. Cena de Nochebuena\n"));
+    assert!(md.contains("## 20261225 - Viernes\n\n**Navidad**\n\n- [ ] 1. [[Ejercicio]]\n"));
+    assert!(md.contains("## 20261231 - Jueves\n\n- [ ] 1. Fin de año\n"));
+    assert_eq!(day_headings(&md).len(), 31);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_profile_swaps_templates_and_rules_for_a_trip() {
+    let dir = temp_dir("profiles");
+    let templates = dir.join("templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(
+        templates.join("travel.md"),
+        "## {{YYYYMMDD}} - {{ weekday }}\n\n- [ ] 1. Disfrutar el {{ profile }}\n",
+    )
+    .unwrap();
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "8",
+        "-p",
+        dir.to_str().unwrap(),
+        "--templates-dir",
+        templates.to_str().unwrap(),
+        "--profile",
+        "travel.md:2026-08-03:2026-08-09",
+    ]);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md = fs::read_to_string(dir.join("TODOS - 202608.md")).unwrap();
+    assert_eq!(count_occurrences(&md, "Disfrutar el travel"), 7);
+    assert!(md.contains("## 20260803 - Lunes\n\n- [ ] 1. Disfrutar el travel\n"));
+    assert!(md.contains("## 20260810 - Lunes\n\n- [ ] 1. [[Ejercicio]]"));
+
+    let ics = fs::read_to_string(dir.join("TODOS - 202608.ics")).unwrap();
+    let events = ics.split("BEGIN:VEVENT").skip(1);
+    for event in events.filter(|event| event.contains("Disfrutar")) {
+        assert!(
+            !event.contains("BEGIN:VALARM"),
+            "Trip days should have no reminders"
+        );
+    }
+    assert!(ics.contains("BEGIN:VALARM"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_recurring_tasks_are_added_to_matching_days() {
+    let dir = temp_dir("recurring");
+    let templates = dir.join("templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(
+        templates.join("recurring.csv"),
+        "rule,priority,description\nday 1,1,Pagar la renta\nlast friday,6,Backup\nevery 2 weeks on tuesday from 2026-08-04,4,Retro del equipo\n",
+    )
+    .unwrap();
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "8",
+        "-p",
+        dir.to_str().unwrap(),
+        "--templates-dir",
+        templates.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md = fs::read_to_string(dir.join("TODOS - 202608.md")).unwrap();
+    assert_eq!(count_occurrences(&md, "Pagar la renta"), 1);
+    assert!(
+        md.contains("## 20260801 - Sábado\n\n- [ ] 1. [[Ejercicio]]\n- [ ] 1. Pagar la renta\n")
+    );
+    assert_eq!(count_occurrences(&md, "- [ ] 6. Backup\n"), 1);
+    assert_eq!(count_occurrences(&md, "Retro del equipo"), 2);
+
+    let ics = fs::read_to_string(dir.join("TODOS - 202608.ics")).unwrap();
+    assert!(ics.contains("SUMMARY:[P1] Pagar la renta"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_task_catalogue_renders_days_and_sets_event_times() {
+    let dir = temp_dir("catalogue");
+    let templates = dir.join("templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(
+        templates.join("tasks.toml"),
+        r#"
+[[tasks]]
+description = "[[Ejercicio]]"
+priority = 1
+
+[[tasks]]
+description = "Trabajar en CBI"
+weekdays = ["lunes", "martes", "miércoles", "jueves", "viernes"]
+priority = 2
+time = "09:00"
+duration = 480
+alarm = 15
+"#,
+    )
+    .unwrap();
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "8",
+        "-p",
+        dir.to_str().unwrap(),
+        "--templates-dir",
+        templates.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let md = fs::read_to_string(dir.join("TODOS - 202608.md")).unwrap();
+    assert!(md.contains("## 20260801 - Sábado\n\n- [ ] 1. [[Ejercicio]]\n\n## 20260802"));
+    assert!(
+        md.contains("## 20260803 - Lunes\n\n- [ ] 1. [[Ejercicio]]\n- [ ] 2. Trabajar en CBI\n\n")
+    );
+    assert_eq!(count_occurrences(&md, "Trabajar en CBI"), 21);
+    assert_eq!(day_headings(&md).len(), 31);
+
+    let ics = fs::read_to_string(dir.join("TODOS - 202608.ics")).unwrap();
+    let work = ics
+        .split("BEGIN:VEVENT")
+        .find(|event| event.contains("Trabajar en CBI"))
+        .unwrap();
+    let time = |key: &str| {
+        let line = work.lines().find(|line| line.starts_with(key)).unwrap();
+        chrono::NaiveDateTime::parse_from_str(&line[key.len()..], "%Y%m%dT%H%M%SZ").unwrap()
+    };
+    assert_eq!(
+        time("DTEND:") - time("DTSTART:"),
+        chrono::Duration::hours(8)
+    );
+    assert!(work.contains("TRIGGER:-PT15M"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_carry_over_open_items_into_the_next_month() {
+    let dir = temp_dir("carry-over");
+    let output = run_cli(2026, 8, &dir);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let august = dir.join("TODOS - 202608.md");
+    let md = fs::read_to_string(&august).unwrap().replace(
+        "## 20260831 - Lunes\n\n- [ ] 1. [[Ejercicio]]\n",
+        "## 20260831 - Lunes\n\n- [x] 1. [[Ejercicio]]\n- [ ] 3. Renovar pasaporte\n",
+    );
+    fs::write(&august, md).unwrap();
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "9",
+        "-p",
+        dir.to_str().unwrap(),
+        "--carry-over",
+    ]);
+    assert!(output.status.success(), "CLI should exit successfully");
+
+    let september = fs::read_to_string(dir.join("TODOS - 202609.md")).unwrap();
+    assert!(september.contains("- [ ] 3. Renovar pasaporte (pendiente del 20260831)\n"));
+    assert_eq!(count_occurrences(&september, "Renovar pasaporte"), 1);
+    assert_eq!(count_occurrences(&september, "Ejercicio"), 30);
+    let ics = fs::read_to_string(dir.join("TODOS - 202609.ics")).unwrap();
+    assert!(ics.contains("Renovar pasaporte"));
+
+    let output = run_cli_args(&[
+        "new",
+        "-y",
+        "2026",
+        "-m",
+        "11",
+        "-p",
+        dir.to_str().unwrap(),
+        "--carry-over",
+    ]);
+    assert!(
+        output.status.success(),
+        "A missing previous file is not an error"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nothing to carry over"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
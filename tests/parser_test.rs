@@ -1,5 +1,6 @@
-use chrono::NaiveDate;
-use todos_cli::parser::{CsvParser, MdParser};
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use todos_cli::diagnostics::Severity;
+use todos_cli::parser::{CsvParser, DueTimeRule, MdParser, TodoStatus};
 
 #[test]
 fn test_md_parser_extracts_single_todo() {
@@ -46,6 +47,93 @@ fn test_md_parser_removes_wiki_links() {
     assert_eq!(items[1].description, "Leer Rust book");
 }
 
+#[test]
+fn test_md_parser_reads_checkbox_states() {
+    let md = "\
+## 20260701 - Miércoles
+- [ ] 1. Ejercicio
+- [x] 2. Task 2
+- [X] 3. Task 3
+- [-] 4. Task 4
+- [>] 5. Task 5
+- [?] 6. Task 6
+";
+    let items = MdParser::parse(md);
+    let statuses: Vec<TodoStatus> = items.iter().map(|item| item.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            TodoStatus::Open,
+            TodoStatus::Done,
+            TodoStatus::Done,
+            TodoStatus::Cancelled,
+            TodoStatus::Deferred,
+        ]
+    );
+    assert_eq!(items[1].description, "Task 2");
+}
+
+#[test]
+fn test_md_parser_reads_inline_times() {
+    let md = "\
+## 20260701 - Miércoles
+- [ ] 1. Trabajar en CBI (09:00-17:00)
+- [ ] 2. Dentista @14:30
+- [ ] 3. Junta a las 14h
+- [ ] 4. Guardia 22:00 – 6:00
+- [ ] 5. Llamar @9h
+- [ ] 6. Leer 2h, 10:30am, 2026-07-01
+";
+    let time = |text: &str| Some(NaiveTime::parse_from_str(text, "%H:%M").unwrap());
+    let times: Vec<_> = MdParser::parse(md)
+        .iter()
+        .map(|item| (item.start, item.end))
+        .collect();
+    assert_eq!(
+        times,
+        vec![
+            (time("09:00"), time("17:00")),
+            (time("14:30"), None),
+            (time("14:00"), None),
+            (time("22:00"), time("06:00")),
+            (time("09:00"), None),
+            (None, None),
+        ]
+    );
+}
+
+#[test]
+fn test_md_parser_reads_inline_durations() {
+    let md = "\
+## 20260701 - Miércoles
+- [ ] 1. Trabajar en [[Ematrix]], 2 horas
+- [ ] 2. Leer 30 minutos
+- [ ] 3. Estudiar 1h30
+- [ ] 4. Run 1 hour 15 min
+- [ ] 5. Stretch (45m)
+- [ ] 6. Junta 14h con 3 mesas, 1,5 horas
+";
+    let minutes: Vec<_> = MdParser::parse(md)
+        .iter()
+        .map(|item| item.duration.map(|duration| duration.num_minutes()))
+        .collect();
+    assert_eq!(
+        minutes,
+        vec![Some(120), Some(30), Some(90), Some(75), Some(45), None]
+    );
+
+    let md = "## 20260701 - Miércoles\n- [ ] 1. Tarea 4294967295 horas\n";
+    let (items, diagnostics) = MdParser::parse_with_diagnostics(md);
+    assert_eq!(items[0].duration, None);
+    assert_eq!(
+        (diagnostics[0].column, diagnostics[0].message.as_str()),
+        (
+            10,
+            "duration of 257698037700 minutes over 24 hours, ignored"
+        )
+    );
+}
+
 #[test]
 fn test_md_parser_handles_empty() {
     let items = MdParser::parse("");
@@ -64,7 +152,7 @@ Martes,1,9:00,30
     assert_eq!(rules.len(), 3);
 
     let lunes1 = &rules[0];
-    assert_eq!(lunes1.weekday, "Lunes");
+    assert_eq!(lunes1.weekday, Weekday::Mon);
     assert_eq!(lunes1.priority, 1);
     assert_eq!(lunes1.hour.format("%H:%M").to_string(), "09:00");
     assert_eq!(lunes1.alarm_minutes, 30);
@@ -79,3 +167,214 @@ Lunes,1,9:00,30
     let rules = CsvParser::parse(csv);
     assert_eq!(rules.len(), 1);
 }
+
+#[test]
+fn test_rule_lookup_matches_weekday_names_across_locales() {
+    let csv = "\
+weekday,priority,hour,minutes
+Lunes,1,9:00,30
+Wednesday,2,16:00,10
+";
+    let rules = CsvParser::parse(csv);
+    assert_eq!(rules[0].weekday, Weekday::Mon);
+    assert_eq!(rules[1].weekday, Weekday::Wed);
+    assert!(DueTimeRule::lookup(&rules, Weekday::Mon, 1).is_some());
+    assert!(DueTimeRule::lookup(&rules, Weekday::Wed, 2).is_some());
+    assert!(DueTimeRule::lookup(&rules, Weekday::Mon, 2).is_none());
+}
+
+#[test]
+fn test_csv_parser_normalises_weekday_spellings() {
+    let csv = "\
+weekday,priority,hour,minutes
+Miercoles,1,9:00,30
+miércoles,2,10:00,30
+Wed,3,11:00,30
+3,4,12:00,30
+Someday,5,13:00,30
+";
+    let rules = CsvParser::parse(csv);
+    assert_eq!(rules.len(), 4);
+    assert!(rules.iter().all(|rule| rule.weekday == Weekday::Wed));
+}
+
+#[test]
+fn test_md_parser_matches_heading_weekday_by_day() {
+    let md = "\
+## 20260701 - miercoles
+- [ ] 1. Ejercicio
+## 20260702 - Thu
+- [ ] 1. Leer
+## 20260703 - Someday
+- [ ] 1. Escribir
+";
+    let (items, diagnostics) = MdParser::parse_with_diagnostics(md);
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].weekday_name, "miercoles");
+    let lines: Vec<usize> = diagnostics.iter().map(|d| d.line).collect();
+    assert_eq!(
+        lines,
+        vec![5],
+        "only 'Someday' is not the weekday of its date"
+    );
+}
+
+#[test]
+fn test_md_parser_checks_heading_weekday_against_date() {
+    let md = "\
+## 20260801 - Lunes
+- [ ] 1. Ejercicio
+## 20260802
+- [ ] 1. Leer
+## 20260803 Lunes
+- [ ] 1. Escribir
+";
+    let (items, diagnostics) = MdParser::parse_with_diagnostics(md);
+    let names: Vec<&str> = items
+        .iter()
+        .map(|item| item.weekday_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Lunes", ""]);
+
+    let found: Vec<(usize, usize, Severity, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.column, d.severity, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                1,
+                15,
+                Severity::Warning,
+                "'Lunes' is not the weekday of 20260801, using Saturday"
+            ),
+            (
+                5,
+                12,
+                Severity::Error,
+                "expected ` - Weekday` after the date, its tasks are skipped"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_md_parser_skips_yaml_frontmatter() {
+    let md = "\
+---
+tags: [\"todos\"]
+planned: 1
+---
+# TODOS 202607
+
+---
+
+## 20260701 - Miércoles
+- [ ] 1. Ejercicio
+";
+    let items = MdParser::parse(md);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].description, "Ejercicio");
+}
+
+#[test]
+fn test_md_parser_reports_malformed_lines() {
+    let md = "\
+---
+planned: 3
+---
+- [ ] 1. Antes del primer día
+## 20260701 - Miércoles
+- [ ] 9. Ejercicio
+- [ ] x. Leer
+- [/] 2. Escribir
+- [ ] Sin prioridad
+## 20260231 - Lunes
+- [ ] 1. Perdida
+- [ ] 2. Olvidada
+## 2026133 - Lunes
+- [ ] 1. Otra
+";
+    let (items, diagnostics) = MdParser::parse_with_diagnostics(md);
+    let priorities: Vec<u8> = items.iter().map(|item| item.priority).collect();
+    assert_eq!(priorities, vec![6, 6]);
+
+    let found: Vec<(usize, usize, Severity)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.column, d.severity))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (4, 1, Severity::Error),
+            (6, 7, Severity::Warning),
+            (7, 7, Severity::Warning),
+            (8, 3, Severity::Error),
+            (9, 7, Severity::Error),
+            (10, 4, Severity::Error),
+            (13, 4, Severity::Error),
+        ]
+    );
+    assert_eq!(
+        diagnostics[6].message,
+        "invalid date '2026133', its tasks are skipped"
+    );
+    assert_eq!(diagnostics[1].message, "priority 9 outside 1 to 6, using 6");
+    assert_eq!(diagnostics[1].source, "- [ ] 9. Ejercicio");
+}
+
+#[test]
+fn test_md_parser_clamps_every_numeric_priority() {
+    let md = "\
+## 20260701 - Miércoles
+- [ ] 300. Ejercicio
+- [ ] 0. Leer
+- [ ] 1a. Escribir
+";
+    let (items, diagnostics) = MdParser::parse_with_diagnostics(md);
+    let priorities: Vec<u8> = items.iter().map(|item| item.priority).collect();
+    assert_eq!(priorities, vec![6, 1, 6]);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "priority 300 outside 1 to 6, using 6",
+            "priority 0 outside 1 to 6, using 1",
+            "invalid priority '1a', using 6",
+        ]
+    );
+}
+
+#[test]
+fn test_csv_parser_reports_malformed_rows() {
+    let csv = "\
+weekday,priority,hour,minutes
+Lunes,1,9:00,30
+Lunes,1,9:00
+Someday,1,9:00,30
+Lunes,7,9:00,30
+Lunes,2,9am,30
+Lunes,3,9:00,soon
+
+Lunes,4,9:00,
+";
+    let (rules, diagnostics) = CsvParser::parse_with_diagnostics(csv);
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[1].alarm_minutes, 0);
+
+    let found: Vec<(usize, usize, Severity)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.column, d.severity))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (3, 1, Severity::Error),
+            (4, 1, Severity::Error),
+            (5, 7, Severity::Error),
+            (6, 9, Severity::Error),
+            (7, 14, Severity::Warning),
+        ]
+    );
+}